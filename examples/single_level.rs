@@ -1,38 +1,24 @@
-use ldtk_rust::{Level, Project};
+use ldtk_rust::{FileSource, LevelStore};
 
 const BASE_DIR: &str = "assets/";
 const PROJECT_FILE: &str = "SeparateLevelFiles.ldtk";
 
 fn main() {
     let current_level_uid: i64 = 0;
-    let mut current_level_path: Option<String> = None;
-    let mut current_level_data: Option<Level> = None;
 
-    // load the main project file
-    let project = Project::load_project(BASE_DIR.to_owned() + PROJECT_FILE);
+    // load the main project file, but none of the level files yet
+    let mut store = LevelStore::from_source(FileSource::new(BASE_DIR), PROJECT_FILE)
+        .expect("could not load the project")
+        .with_max_levels(2);
 
-    // loop through all the levels and get the relative path to
-    // the level that matches the current_level_id
-    for level in project.levels.iter() {
-        if level.uid == current_level_uid {
-            current_level_path = level.external_rel_path.clone();
-            break;
-        }
-    }
-
-    // try to find a file for the level id.
-    match current_level_path {
-        Some(t) => current_level_data = Some(Level::new(BASE_DIR.to_owned() + &t)),
-        None => println!("that level ID is not correct."),
-    };
-
-    // print some data
-    match current_level_data {
-        Some(t) => println!(
+    // the level file is read the first time the level is asked for
+    match store.get(current_level_uid) {
+        Ok(Some(level)) => println!(
             "Level {} has {} layer instaces.",
             current_level_uid,
-            t.layer_instances.unwrap().len()
+            level.layer_instances.as_ref().map_or(0, |l| l.len())
         ),
-        None => println!("no level data"),
+        Ok(None) => println!("that level ID is not correct."),
+        Err(e) => println!("no level data: {e}"),
     }
 }
@@ -0,0 +1,247 @@
+// Loading a project from a zip archive (a "pak" file) holding the .ldtk
+// file, its external levels and the files it references, and writing
+// such an archive. Enabled with the `archive` cargo feature.
+
+use crate::{AssetSource, LdtkError, Project, asset, parse_project};
+use std::{
+    borrow::Cow,
+    collections::BTreeSet,
+    io::{self, Read, Seek, Write},
+    path::PathBuf,
+    sync::{Mutex, PoisonError},
+};
+use zip::{ZipArchive, ZipWriter, write::SimpleFileOptions};
+
+/// A zip archive used as an [`AssetSource`]. Paths are resolved relative to
+/// the directory the project file is in inside the archive, the same way
+/// LDtk resolves them on disk.
+pub struct ArchiveSource<R> {
+    archive: Mutex<ZipArchive<R>>,
+    // directory of the project file inside the archive, with a trailing
+    // slash unless it's the archive root
+    root: String,
+    project_file: String,
+}
+
+impl<R: Read + Seek> ArchiveSource<R> {
+    /// Open an archive that contains exactly one `.ldtk` file.
+    pub fn new(reader: R) -> Result<Self, LdtkError> {
+        let archive = ZipArchive::new(reader).map_err(zip_error)?;
+        let mut projects = archive.file_names().filter(|n| n.ends_with(".ldtk"));
+        let project = match (projects.next(), projects.next()) {
+            (Some(p), None) => p.to_string(),
+            (None, _) => {
+                return Err(archive_error(
+                    io::ErrorKind::NotFound,
+                    "no .ldtk file in archive",
+                ));
+            }
+            (Some(_), Some(_)) => {
+                return Err(archive_error(
+                    io::ErrorKind::InvalidInput,
+                    "more than one .ldtk file in archive, use ArchiveSource::with_project()",
+                ));
+            }
+        };
+        drop(projects);
+        Ok(ArchiveSource::from_parts(archive, &project))
+    }
+
+    /// Open an archive and use the project file at `project_path` (for
+    /// example `assets/game.ldtk`).
+    pub fn with_project(reader: R, project_path: &str) -> Result<Self, LdtkError> {
+        let archive = ZipArchive::new(reader).map_err(zip_error)?;
+        Ok(ArchiveSource::from_parts(archive, project_path))
+    }
+
+    fn from_parts(archive: ZipArchive<R>, project_path: &str) -> Self {
+        let project_path = asset::normalize(project_path);
+        let (root, project_file) = match project_path.rsplit_once('/') {
+            Some((dir, file)) => (format!("{dir}/"), file.to_string()),
+            None => (String::new(), project_path),
+        };
+        ArchiveSource {
+            archive: Mutex::new(archive),
+            root,
+            project_file,
+        }
+    }
+
+    /// Name of the project file, relative to the source.
+    pub fn project_file(&self) -> &str {
+        &self.project_file
+    }
+
+    fn entry_name(&self, path: &str) -> String {
+        asset::normalize(&format!("{}{}", self.root, path))
+    }
+}
+
+impl<R: Read + Seek> AssetSource for ArchiveSource<R> {
+    fn read(&self, path: &str) -> io::Result<Cow<'_, [u8]>> {
+        let mut archive = self.archive.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut file = archive.by_name(&self.entry_name(path))?;
+        // the size comes from the archive, don't trust it with more than
+        // a reasonable allocation up front
+        let mut buf = Vec::with_capacity(file.size().min(MAX_PREALLOC) as usize);
+        file.read_to_end(&mut buf)?;
+        Ok(Cow::Owned(buf))
+    }
+
+    fn location(&self, path: &str) -> PathBuf {
+        PathBuf::from(self.entry_name(path))
+    }
+}
+
+// Largest buffer reserved before reading an entry, bigger files grow it
+// as they are read.
+const MAX_PREALLOC: u64 = 16 * 1024 * 1024;
+
+fn zip_error(e: zip::result::ZipError) -> LdtkError {
+    LdtkError::from_io(e.into(), None)
+}
+
+fn archive_error(kind: io::ErrorKind, msg: &str) -> LdtkError {
+    LdtkError::from_io(io::Error::new(kind, msg), None)
+}
+
+impl Project {
+    pub fn from_archive<R: Read + Seek>(archive: &ArchiveSource<R>) -> Self {
+        Project::try_from_archive(archive).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    // Load the project and its external levels from an archive. Keep the
+    // ArchiveSource around to read tileset and background images with it.
+    pub fn try_from_archive<R: Read + Seek>(archive: &ArchiveSource<R>) -> Result<Self, LdtkError> {
+        Project::try_from_source(archive, archive.project_file())
+    }
+
+    // Bundle a project file and everything it references (external
+    // levels, tileset images, level backgrounds and external enum files)
+    // into a zip archive that try_from_archive() can load. `project_file`
+    // is the path of the project in `source`, e.g. "game.ldtk" or
+    // "assets/game.ldtk". The project goes into the archive under its file
+    // name, next to the files it references.
+    pub fn write_archive<S: AssetSource + ?Sized, W: Write + Seek>(
+        source: &S,
+        project_file: &str,
+        writer: W,
+    ) -> Result<W, LdtkError> {
+        let b = asset::read(source, project_file)?;
+        let project = parse_project(&b)
+            .map_err(|e| LdtkError::from_json(e, Some(source.location(project_file))))?;
+
+        // the files are relative to the project's directory in `source`
+        let project_path = asset::normalize(project_file);
+        let (dir, name) = match project_path.rsplit_once('/') {
+            Some((dir, name)) => (format!("{dir}/"), name),
+            None => (String::new(), project_path.as_str()),
+        };
+
+        let levels = project
+            .levels
+            .iter()
+            .chain(project.worlds.iter().flat_map(|w| w.levels.iter()));
+        let mut paths: Vec<&String> = Vec::new();
+        for level in levels {
+            paths.extend(level.external_rel_path.iter());
+            paths.extend(level.bg_rel_path.iter());
+        }
+        paths.extend(
+            project
+                .defs
+                .tilesets
+                .iter()
+                .filter_map(|t| t.rel_path.as_ref()),
+        );
+        paths.extend(
+            project
+                .defs
+                .external_enums
+                .iter()
+                .filter_map(|e| e.external_rel_path.as_ref()),
+        );
+        // "./tiles.png" and "tiles.png" are the same file, and the same
+        // zip entry
+        let files: BTreeSet<String> = paths.into_iter().map(|f| asset::normalize(f)).collect();
+
+        // files outside of the project's directory ("../gfx/tiles.png")
+        // need the project to sit that many directories deep
+        let depth = files
+            .iter()
+            .map(|f| f.split('/').take_while(|p| *p == "..").count())
+            .max()
+            .unwrap_or(0);
+        let root = "project/".repeat(depth);
+
+        let options = SimpleFileOptions::default();
+        let mut zip = ZipWriter::new(writer);
+        let mut write = |path: &str, bytes: &[u8]| -> Result<(), LdtkError> {
+            zip.start_file(asset::normalize(&format!("{root}{path}")), options)
+                .map_err(zip_error)?;
+            zip.write_all(bytes)
+                .map_err(|e| LdtkError::from_io(e, None))
+        };
+        write(name, &b)?;
+        for f in &files {
+            write(f, &asset::read(source, &format!("{dir}{f}"))?)?;
+        }
+        zip.finish().map_err(zip_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemorySource;
+    use serde_json::Value;
+    use std::io::Cursor;
+
+    // SeparateLevelFiles.ldtk in a "data" directory, with the same
+    // background image spelled two ways.
+    fn source() -> MemorySource {
+        let mut json: Value =
+            serde_json::from_slice(include_bytes!("../assets/SeparateLevelFiles.ldtk")).unwrap();
+        let levels = json["levels"].as_array_mut().unwrap();
+        levels[0]["bgRelPath"] = "./Cavernas_by_Adam_Saltsman.png".into();
+        levels[1]["bgRelPath"] = "Cavernas_by_Adam_Saltsman.png".into();
+        levels[2]["externalRelPath"] = "SeparateLevelFiles\\0002-Level_C.ldtkl".into();
+
+        let mut source = MemorySource::new();
+        source.insert("data/game.ldtk", serde_json::to_vec(&json).unwrap());
+        source.insert(
+            "data/Cavernas_by_Adam_Saltsman.png",
+            include_bytes!("../assets/Cavernas_by_Adam_Saltsman.png").to_vec(),
+        );
+        for level in ["0000-Level_A", "0001-Level_B", "0002-Level_C"] {
+            let path = format!("assets/SeparateLevelFiles/{level}.ldtkl");
+            let b = std::fs::read(path).unwrap();
+            source.insert(format!("data/SeparateLevelFiles/{level}.ldtkl"), b);
+        }
+        source
+    }
+
+    #[test]
+    fn same_file_spelled_differently_is_written_once() {
+        let zip = Project::write_archive(&source(), "data/game.ldtk", Cursor::new(Vec::new()))
+            .unwrap()
+            .into_inner();
+        let archive = ArchiveSource::new(Cursor::new(zip)).unwrap();
+        let names: BTreeSet<String> = {
+            let zip = archive.archive.lock().unwrap();
+            zip.file_names().map(str::to_string).collect()
+        };
+        let expected = [
+            "Cavernas_by_Adam_Saltsman.png",
+            "SeparateLevelFiles/0000-Level_A.ldtkl",
+            "SeparateLevelFiles/0001-Level_B.ldtkl",
+            "SeparateLevelFiles/0002-Level_C.ldtkl",
+            "game.ldtk",
+        ];
+        assert_eq!(names, expected.map(String::from).into());
+
+        let project = Project::try_from_archive(&archive).unwrap();
+        assert_eq!(archive.project_file(), "game.ldtk");
+        assert!(project.iter_levels().all(|l| l.layer_instances.is_some()));
+    }
+}
@@ -0,0 +1,197 @@
+// Where the loader gets its bytes from. LDtk stores every reference to
+// another file (external levels, tileset images, level backgrounds,
+// external enums) as a path relative to the project file, so a source is
+// rooted at the directory that contains the project file and is asked
+// for those relative paths as they appear in the JSON.
+
+use crate::{EnumDefinition, LdtkError, Level, TilesetDefinition};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+};
+
+/// Something the loader can read project, level and asset files from.
+///
+/// Paths are relative to the directory of the project file and use `/` as
+/// the separator, exactly as LDtk writes them (for example
+/// `SeparateLevelFiles/0000-Level_A.ldtkl` or `../gfx/tiles.png`).
+pub trait AssetSource {
+    /// Read the whole file at `path`.
+    fn read(&self, path: &str) -> io::Result<Cow<'_, [u8]>>;
+
+    /// How to refer to `path` in error messages. Defaults to the path
+    /// itself.
+    fn location(&self, path: &str) -> PathBuf {
+        PathBuf::from(path)
+    }
+}
+
+/// Reads files from a directory on disk.
+#[derive(Debug, Clone)]
+pub struct FileSource {
+    root: PathBuf,
+}
+
+impl FileSource {
+    /// `root` is the directory the project file is in.
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        FileSource {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+impl AssetSource for FileSource {
+    fn read(&self, path: &str) -> io::Result<Cow<'_, [u8]>> {
+        std::fs::read(self.location(path)).map(Cow::Owned)
+    }
+
+    fn location(&self, path: &str) -> PathBuf {
+        self.root.join(path)
+    }
+}
+
+/// Files held in memory, keyed by their path relative to the project.
+#[derive(Debug, Clone, Default)]
+pub struct MemorySource {
+    files: HashMap<String, Vec<u8>>,
+}
+
+impl MemorySource {
+    pub fn new() -> Self {
+        MemorySource::default()
+    }
+
+    /// Add (or replace) a file.
+    pub fn insert<S: AsRef<str>>(&mut self, path: S, bytes: Vec<u8>) {
+        self.files.insert(normalize(path.as_ref()), bytes);
+    }
+
+    pub fn remove(&mut self, path: &str) -> Option<Vec<u8>> {
+        self.files.remove(&normalize(path))
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<(S, Vec<u8>)> for MemorySource {
+    fn from_iter<I: IntoIterator<Item = (S, Vec<u8>)>>(iter: I) -> Self {
+        let mut o = MemorySource::new();
+        for (path, bytes) in iter {
+            o.insert(path, bytes);
+        }
+        o
+    }
+}
+
+impl AssetSource for MemorySource {
+    fn read(&self, path: &str) -> io::Result<Cow<'_, [u8]>> {
+        self.files
+            .get(&normalize(path))
+            .map(|b| Cow::Borrowed(b.as_slice()))
+            .ok_or_else(|| not_found(path))
+    }
+}
+
+/// Files compiled into the binary with `include_bytes!`.
+///
+/// ```ignore
+/// static ASSETS: EmbeddedSource = EmbeddedSource::new(&[
+///     ("game.ldtk", include_bytes!("../assets/game.ldtk")),
+///     ("game/Level_0.ldtkl", include_bytes!("../assets/game/Level_0.ldtkl")),
+/// ]);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct EmbeddedSource {
+    files: &'static [(&'static str, &'static [u8])],
+}
+
+impl EmbeddedSource {
+    pub const fn new(files: &'static [(&'static str, &'static [u8])]) -> Self {
+        EmbeddedSource { files }
+    }
+}
+
+impl AssetSource for EmbeddedSource {
+    fn read(&self, path: &str) -> io::Result<Cow<'_, [u8]>> {
+        let path = normalize(path);
+        self.files
+            .iter()
+            .find(|(p, _)| normalize(p) == path)
+            .map(|(_, b)| Cow::Borrowed(*b))
+            .ok_or_else(|| not_found(&path))
+    }
+}
+
+fn not_found(path: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, format!("{path} not found"))
+}
+
+// Turn a relative path into the key used by the in-memory sources:
+// forward slashes, no "." segments and ".." applied where possible.
+pub(crate) fn normalize(path: &str) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split(['/', '\\']) {
+        match part {
+            "" | "." => {}
+            ".." if parts.last().is_some_and(|p| *p != "..") => {
+                parts.pop();
+            }
+            p => parts.push(p),
+        }
+    }
+    parts.join("/")
+}
+
+// Read a file from a source, tagging a failure with where it came from.
+pub(crate) fn read<'a, S: AssetSource + ?Sized>(
+    source: &'a S,
+    path: &str,
+) -> Result<Cow<'a, [u8]>, LdtkError> {
+    source
+        .read(path)
+        .map_err(|e| LdtkError::from_io(e, Some(source.location(path))))
+}
+
+fn read_optional<'a, S: AssetSource + ?Sized>(
+    source: &'a S,
+    path: Option<&String>,
+) -> Result<Option<Cow<'a, [u8]>>, LdtkError> {
+    path.map(|p| read(source, p)).transpose()
+}
+
+impl TilesetDefinition {
+    /// Read the tileset image. Returns None for tilesets that have no
+    /// image file, like the embedded LDtk icons.
+    pub fn read_image<'a, S: AssetSource + ?Sized>(
+        &self,
+        source: &'a S,
+    ) -> Result<Option<Cow<'a, [u8]>>, LdtkError> {
+        read_optional(source, self.rel_path.as_ref())
+    }
+}
+
+impl Level {
+    /// Read the level's background image, if it has one.
+    pub fn read_bg_image<'a, S: AssetSource + ?Sized>(
+        &self,
+        source: &'a S,
+    ) -> Result<Option<Cow<'a, [u8]>>, LdtkError> {
+        read_optional(source, self.bg_rel_path.as_ref())
+    }
+}
+
+impl EnumDefinition {
+    /// Read the file an external enum was imported from. Returns None for
+    /// enums defined in the project itself.
+    pub fn read_external_file<'a, S: AssetSource + ?Sized>(
+        &self,
+        source: &'a S,
+    ) -> Result<Option<Cow<'a, [u8]>>, LdtkError> {
+        read_optional(source, self.external_rel_path.as_ref())
+    }
+}
@@ -0,0 +1,199 @@
+// Loading without blocking the game loop. AsyncAssetSource is the async
+// counterpart of AssetSource. Nothing here depends on a particular
+// runtime: the futures only wait on the source, so they can be polled by
+// whatever executor the game already uses.
+
+#[cfg(feature = "tracing")]
+use crate::level_span;
+use crate::observe::debug;
+use crate::{
+    AssetSource, LdtkError, Level, LoadObserver, Project, external_level_error, parse_project,
+    parse_standalone_level, start_external_level,
+};
+use std::{borrow::Cow, future::Future, io, path::PathBuf};
+
+/// Something the async loaders can read files from, with the same paths
+/// as an [`AssetSource`].
+///
+/// Every `AssetSource` is also an `AsyncAssetSource` whose reads complete
+/// right away (blocking while they read). To load without blocking,
+/// implement this trait on top of your runtime's file or network API.
+///
+/// The futures returned by `read` are `Send`, so with a `Sync` source the
+/// loaders' futures are `Send` too and can be spawned on a multi-threaded
+/// task pool.
+pub trait AsyncAssetSource {
+    /// Read the whole file at `path`.
+    fn read(&self, path: &str) -> impl Future<Output = io::Result<Vec<u8>>> + Send;
+
+    /// How to refer to `path` in error messages. Defaults to the path
+    /// itself.
+    fn location(&self, path: &str) -> PathBuf {
+        PathBuf::from(path)
+    }
+}
+
+impl<T: AssetSource + ?Sized> AsyncAssetSource for T {
+    fn read(&self, path: &str) -> impl Future<Output = io::Result<Vec<u8>>> + Send {
+        std::future::ready(AssetSource::read(self, path).map(Cow::into_owned))
+    }
+
+    fn location(&self, path: &str) -> PathBuf {
+        AssetSource::location(self, path)
+    }
+}
+
+/// Handed to the progress callback of
+/// [`Project::load_async_with_progress`] after each external level.
+#[derive(Debug, Clone, Copy)]
+pub struct LoadProgress<'a> {
+    /// Number of levels loaded so far, this one included.
+    pub loaded: usize,
+    pub total: usize,
+    /// The level that was just loaded.
+    pub level: &'a Level,
+}
+
+async fn read<S: AsyncAssetSource + ?Sized>(source: &S, path: &str) -> Result<Vec<u8>, LdtkError> {
+    source
+        .read(path)
+        .await
+        .map_err(|e| LdtkError::from_io(e, Some(source.location(path))))
+}
+
+impl Project {
+    /// Load a project and its external levels from an async source.
+    pub async fn load_async<S: AsyncAssetSource + ?Sized>(
+        source: &S,
+        f: &str,
+    ) -> Result<Self, LdtkError> {
+        Project::load_async_with_progress(source, f, |_| {}).await
+    }
+
+    /// Same as [`Project::load_async`], calling `progress` each time an
+    /// external level has been loaded, for example to drive a loading
+    /// screen. Levels are loaded one after the other, in order.
+    pub async fn load_async_with_progress<S, F>(
+        source: &S,
+        f: &str,
+        progress: F,
+    ) -> Result<Self, LdtkError>
+    where
+        S: AsyncAssetSource + ?Sized,
+        F: FnMut(LoadProgress<'_>),
+    {
+        Project::load_async_inner(source, f, &(), progress).await
+    }
+
+    /// Same as [`Project::load_async`], reporting to a [`LoadObserver`]
+    /// like [`Project::try_from_source_with_observer`] does.
+    pub async fn load_async_with_observer<S, O>(
+        source: &S,
+        f: &str,
+        observer: &O,
+    ) -> Result<Self, LdtkError>
+    where
+        S: AsyncAssetSource + ?Sized,
+        O: LoadObserver + ?Sized,
+    {
+        Project::load_async_inner(source, f, observer, |_| {}).await
+    }
+
+    async fn load_async_inner<S, O, F>(
+        source: &S,
+        f: &str,
+        observer: &O,
+        mut progress: F,
+    ) -> Result<Self, LdtkError>
+    where
+        S: AsyncAssetSource + ?Sized,
+        O: LoadObserver + ?Sized,
+        F: FnMut(LoadProgress<'_>),
+    {
+        let b = read(source, f).await?;
+        observer.bytes_read(f, b.len());
+        let mut o =
+            parse_project(&b).map_err(|e| LdtkError::from_json(e, Some(source.location(f))))?;
+        observer.project_parsed(&o);
+        if !o.external_levels {
+            return Ok(o);
+        }
+
+        let total = o.iter_levels().count();
+        let mut levels = Vec::with_capacity(total);
+        for (world, level) in o.iter_levels_with_world() {
+            let load = async {
+                let rel_path = start_external_level(level, observer)?;
+                debug!("opening {}", source.location(rel_path).display());
+                let b = source
+                    .read(rel_path)
+                    .await
+                    .map_err(|e| external_level_error(level, e, source.location(rel_path)))?;
+                o.finish_external_level(source, &world, rel_path, &b, observer)
+            };
+            // the span is entered each time the future is polled, not
+            // held across the read
+            #[cfg(feature = "tracing")]
+            let load = tracing::Instrument::instrument(load, level_span(level));
+            let full = load.await?;
+            progress(LoadProgress {
+                loaded: levels.len() + 1,
+                total,
+                level: &full,
+            });
+            levels.push(full);
+        }
+        o.replace_levels(levels);
+        Ok(o)
+    }
+}
+
+impl Level {
+    /// Read a single external level file from an async source.
+    pub async fn load_async<S: AsyncAssetSource + ?Sized>(
+        source: &S,
+        f: &str,
+    ) -> Result<Self, LdtkError> {
+        let b = read(source, f).await?;
+        parse_standalone_level(&b).map_err(|e| LdtkError::from_json(e, Some(source.location(f))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileSource;
+    use std::{pin::pin, task, thread};
+
+    // A stand-in for a multi-threaded task pool: the future has to be Send
+    // to be polled on another thread.
+    fn spawn<F: Future + Send + 'static>(f: F) -> thread::JoinHandle<F::Output>
+    where
+        F::Output: Send,
+    {
+        thread::spawn(move || {
+            let mut f = pin!(f);
+            let mut cx = task::Context::from_waker(task::Waker::noop());
+            loop {
+                if let task::Poll::Ready(out) = f.as_mut().poll(&mut cx) {
+                    return out;
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn futures_are_send() {
+        let project = spawn(async {
+            let source = FileSource::new("assets");
+            Project::load_async(&source, "SeparateLevelFiles.ldtk").await
+        });
+        let level = spawn(async {
+            let source = FileSource::new("assets/SeparateLevelFiles");
+            Level::load_async(&source, "0000-Level_A.ldtkl").await
+        });
+        let project = project.join().unwrap().unwrap();
+        assert!(project.iter_levels().all(|l| l.layer_instances.is_some()));
+        assert_eq!(level.join().unwrap().unwrap().identifier, "Level_A");
+    }
+}
@@ -0,0 +1,467 @@
+// Generates Rust source from a project's definitions: an enum per LDtk
+// enum, a struct per entity (and one for the level fields) that can be
+// filled with from_fields(), and constants for the layer and tileset
+// identifiers. Meant to run from a build script, so that renaming
+// something in the editor breaks the build instead of the game.
+
+use crate::{Definitions, EnumDefinition, FieldDefinition, FieldType, LdtkError};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Write},
+    path::Path,
+};
+
+/// Writes Rust types for a project's [`Definitions`].
+///
+/// ```ignore
+/// // build.rs
+/// fn main() {
+///     println!("cargo:rerun-if-changed=assets/game.ldtk");
+///     let project = ldtk_rust::Project::load_project("assets/game.ldtk");
+///     let out = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("ldtk.rs");
+///     ldtk_rust::CodeGenerator::new(&project.defs).write_to(out).unwrap();
+/// }
+///
+/// // src/main.rs
+/// mod ldtk {
+///     include!(concat!(env!("OUT_DIR"), "/ldtk.rs"));
+/// }
+/// let door: ldtk::entities::Door = entity.fields_as()?;
+/// ```
+///
+/// The generated code has these modules and types:
+/// - `enums`: an enum per enum definition (external enums included),
+///   with a variant per value
+/// - `entities`: a struct per entity definition, with a field per custom
+///   field and an `IDENTIFIER` constant
+/// - `LevelFields`: a struct with the custom level fields
+/// - `layers` and `tilesets`: a constant per identifier
+///
+/// Structs and enums derive `serde::Deserialize`, so the crate using them
+/// needs `serde` as a dependency. Names are converted to the usual Rust
+/// case, with `#[serde(rename)]` pointing at the LDtk identifier, and
+/// numbered when two identifiers end up with the same name (`MyDoor` and
+/// `MyDoor2` for `my_door` and `MyDoor`).
+#[derive(Debug, Clone, Copy)]
+pub struct CodeGenerator<'a> {
+    defs: &'a Definitions,
+}
+
+impl<'a> CodeGenerator<'a> {
+    pub fn new(defs: &'a Definitions) -> Self {
+        CodeGenerator { defs }
+    }
+
+    /// The generated module, as Rust source.
+    pub fn generate(&self) -> String {
+        let mut out = String::new();
+        self.write(&mut out)
+            .expect("writing to a String can't fail");
+        out
+    }
+
+    /// Write the generated source to `path`. The file is left alone when
+    /// it already has the same contents, so cargo doesn't rebuild for
+    /// nothing.
+    pub fn write_to<P: AsRef<Path>>(&self, path: P) -> Result<(), LdtkError> {
+        let path = path.as_ref();
+        let source = self.generate();
+        if std::fs::read(path).is_ok_and(|old| old == source.as_bytes()) {
+            return Ok(());
+        }
+        std::fs::write(path, source).map_err(|e| LdtkError::from_io(e, Some(path.to_path_buf())))
+    }
+
+    fn write(&self, out: &mut String) -> fmt::Result {
+        writeln!(
+            out,
+            "// Generated by ldtk_rust from the project definitions."
+        )?;
+        writeln!(out, "// Do not edit, changes will be overwritten.")?;
+
+        let enums = EnumNames::new(self.defs);
+        writeln!(out)?;
+        writeln!(out, "#[allow(dead_code)]")?;
+        writeln!(out, "pub mod enums {{")?;
+        for def in &self.defs.enums {
+            write_enum(out, &enums.local[def.identifier.as_str()], def)?;
+        }
+        for def in &self.defs.external_enums {
+            write_enum(out, &enums.external[def.identifier.as_str()], def)?;
+        }
+        writeln!(out, "}}")?;
+
+        let mut used = HashSet::new();
+        writeln!(out)?;
+        writeln!(out, "#[allow(dead_code)]")?;
+        writeln!(out, "pub mod entities {{")?;
+        for def in &self.defs.entities {
+            let name = unique(&mut used, type_name(&def.identifier));
+            writeln!(out)?;
+            write_doc(out, "    ", def.doc.as_deref())?;
+            write_struct(out, "    ", &name, &def.field_defs, &enums, "super::")?;
+            writeln!(out, "    impl {} {{", name)?;
+            writeln!(
+                out,
+                "        pub const IDENTIFIER: &str = {:?};",
+                def.identifier
+            )?;
+            writeln!(out, "    }}")?;
+        }
+        writeln!(out, "}}")?;
+
+        writeln!(out)?;
+        writeln!(out, "/// The custom fields of levels.")?;
+        writeln!(out, "#[allow(dead_code)]")?;
+        write_struct(out, "", "LevelFields", &self.defs.level_fields, &enums, "")?;
+
+        let layers = self.defs.layers.iter().map(|l| &l.identifier);
+        write_constants(out, "layers", layers)?;
+        let tilesets = self.defs.tilesets.iter().map(|t| &t.identifier);
+        write_constants(out, "tilesets", tilesets)
+    }
+}
+
+// The type names of the enums, numbered like the other names when two
+// identifiers convert to the same one. The fields of type
+// `LocalEnum.Name` and `ExternEnum.Name` look them up here.
+struct EnumNames<'a> {
+    local: HashMap<&'a str, String>,
+    external: HashMap<&'a str, String>,
+}
+
+impl<'a> EnumNames<'a> {
+    fn new(defs: &'a Definitions) -> Self {
+        let mut used = HashSet::new();
+        let mut names = |enums: &'a [EnumDefinition]| -> HashMap<&'a str, String> {
+            enums
+                .iter()
+                .map(|d| {
+                    let name = unique(&mut used, type_name(&d.identifier));
+                    (d.identifier.as_str(), name)
+                })
+                .collect()
+        };
+        let local = names(&defs.enums);
+        let external = names(&defs.external_enums);
+        EnumNames { local, external }
+    }
+}
+
+fn write_enum(out: &mut String, name: &str, def: &EnumDefinition) -> fmt::Result {
+    let mut used = HashSet::new();
+    let variants: Vec<(String, &str)> = def
+        .values
+        .iter()
+        .map(|v| (unique(&mut used, type_name(&v.id)), v.id.as_str()))
+        .collect();
+
+    writeln!(out)?;
+    writeln!(
+        out,
+        "    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ::serde::Deserialize)]"
+    )?;
+    writeln!(out, "    pub enum {} {{", name)?;
+    for (variant, id) in &variants {
+        write_rename(out, "        ", variant, id)?;
+        writeln!(out, "        {},", variant)?;
+    }
+    writeln!(out, "    }}")?;
+
+    writeln!(out, "    impl {} {{", name)?;
+    let all: Vec<String> = variants
+        .iter()
+        .map(|(v, _)| format!("{name}::{v}"))
+        .collect();
+    writeln!(
+        out,
+        "        pub const ALL: [{}; {}] = [{}];",
+        name,
+        all.len(),
+        all.join(", ")
+    )?;
+    writeln!(out, "        /// The value's identifier in LDtk.")?;
+    writeln!(out, "        pub fn identifier(self) -> &'static str {{")?;
+    writeln!(out, "            match self {{")?;
+    for (variant, id) in &variants {
+        writeln!(out, "                {}::{} => {:?},", name, variant, id)?;
+    }
+    writeln!(out, "            }}")?;
+    writeln!(out, "        }}")?;
+    writeln!(out, "    }}")
+}
+
+// A struct with a field per field definition. `to_root` is the path from
+// the struct's module to the generated module's root, for the enums.
+fn write_struct(
+    out: &mut String,
+    indent: &str,
+    name: &str,
+    fields: &[FieldDefinition],
+    enums: &EnumNames,
+    to_root: &str,
+) -> fmt::Result {
+    let mut used = HashSet::new();
+    writeln!(out, "{indent}#[derive(Debug, Clone, ::serde::Deserialize)]")?;
+    writeln!(out, "{indent}pub struct {} {{", name)?;
+    for def in fields {
+        let field = unique(&mut used, field_name(&def.identifier));
+        write_doc(out, &format!("{indent}    "), def.doc.as_deref())?;
+        write_rename(out, &format!("{indent}    "), &field, &def.identifier)?;
+        writeln!(
+            out,
+            "{indent}    pub {}: {},",
+            field,
+            field_type(def, enums, to_root)
+        )?;
+    }
+    writeln!(out, "{indent}}}")
+}
+
+fn write_constants<'a, I: Iterator<Item = &'a String>>(
+    out: &mut String,
+    module: &str,
+    identifiers: I,
+) -> fmt::Result {
+    let mut used = HashSet::new();
+    writeln!(out)?;
+    writeln!(out, "#[allow(dead_code)]")?;
+    writeln!(out, "pub mod {} {{", module)?;
+    for id in identifiers {
+        let name = unique(&mut used, constant_name(id));
+        writeln!(out, "    pub const {}: &str = {:?};", name, id)?;
+    }
+    writeln!(out, "}}")
+}
+
+fn write_doc(out: &mut String, indent: &str, doc: Option<&str>) -> fmt::Result {
+    for line in doc.into_iter().flat_map(str::lines) {
+        writeln!(out, "{indent}/// {}", line.trim_end())?;
+    }
+    Ok(())
+}
+
+fn write_rename(out: &mut String, indent: &str, name: &str, identifier: &str) -> fmt::Result {
+    if name.trim_start_matches("r#") != identifier {
+        writeln!(out, "{indent}#[serde(rename = {:?})]", identifier)?;
+    }
+    Ok(())
+}
+
+// The Rust type of a field. Nullable fields become Options (for arrays,
+// the elements can be null).
+fn field_type(def: &FieldDefinition, enums: &EnumNames, to_root: &str) -> String {
+    match FieldType::parse(&def.field_definition_type) {
+        Some(FieldType::Array(item)) if def.can_be_null => {
+            format!("Vec<Option<{}>>", rust_type(&item, enums, to_root))
+        }
+        Some(t @ FieldType::Array(_)) => rust_type(&t, enums, to_root),
+        Some(t) if def.can_be_null => format!("Option<{}>", rust_type(&t, enums, to_root)),
+        Some(t) => rust_type(&t, enums, to_root),
+        None => "::serde_json::Value".to_string(),
+    }
+}
+
+fn rust_type(t: &FieldType, enums: &EnumNames, to_root: &str) -> String {
+    match t {
+        FieldType::Int => "i64".to_string(),
+        FieldType::Float => "f64".to_string(),
+        FieldType::Bool => "bool".to_string(),
+        FieldType::String | FieldType::Multilines | FieldType::FilePath => "String".to_string(),
+        FieldType::Color => "::ldtk_rust::Color".to_string(),
+        FieldType::Point => "::ldtk_rust::GridPoint".to_string(),
+        FieldType::EntityRef => "::ldtk_rust::EntityRef".to_string(),
+        FieldType::Tile => "::ldtk_rust::TilesetRectangle".to_string(),
+        FieldType::LocalEnum(id) => format!("{to_root}enums::{}", enum_name(&enums.local, id)),
+        FieldType::ExternEnum(id) => {
+            format!("{to_root}enums::{}", enum_name(&enums.external, id))
+        }
+        FieldType::Array(item) => format!("Vec<{}>", rust_type(item, enums, to_root)),
+    }
+}
+
+// The type name of an enum, converted as is if there's no such enum (the
+// generated code then won't compile, as it should).
+fn enum_name(names: &HashMap<&str, String>, identifier: &str) -> String {
+    names
+        .get(identifier)
+        .cloned()
+        .unwrap_or_else(|| type_name(identifier))
+}
+
+// Split an identifier into words at underscores and case changes
+// ("levelName", "LEVEL_NAME" and "HTTPServer" become two words each).
+fn words(s: &str) -> Vec<String> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        if c.is_uppercase() && !word.is_empty() {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_lower) {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn type_name(s: &str) -> String {
+    let name: String = words(s)
+        .iter()
+        .map(|w| {
+            let mut chars = w.chars();
+            let first = chars.next().map(|c| c.to_uppercase().collect::<String>());
+            first.unwrap_or_default() + &chars.as_str().to_lowercase()
+        })
+        .collect();
+    valid_ident(name)
+}
+
+fn field_name(s: &str) -> String {
+    let words: Vec<String> = words(s).iter().map(|w| w.to_lowercase()).collect();
+    valid_ident(words.join("_"))
+}
+
+fn constant_name(s: &str) -> String {
+    let words: Vec<String> = words(s).iter().map(|w| w.to_uppercase()).collect();
+    valid_ident(words.join("_"))
+}
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "gen", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where",
+    "while", "abstract", "become", "box", "do", "final", "macro", "override", "priv", "try",
+    "typeof", "unsized", "virtual", "yield",
+];
+
+// Make sure a converted name can be used as an identifier.
+fn valid_ident(name: String) -> String {
+    if name.is_empty() || name.starts_with(|c: char| c.is_numeric()) {
+        format!("_{name}")
+    } else if matches!(name.as_str(), "self" | "Self" | "super" | "crate") {
+        format!("{name}_")
+    } else if KEYWORDS.contains(&name.as_str()) {
+        format!("r#{name}")
+    } else {
+        name
+    }
+}
+
+// Two identifiers can convert to the same name ("hp" and "HP"), number
+// the later ones. A numbered keyword isn't a keyword anymore, so it
+// loses its `r#`.
+fn unique(used: &mut HashSet<String>, name: String) -> String {
+    let base = name.strip_prefix("r#").unwrap_or(&name);
+    let mut candidate = name.clone();
+    let mut n = 2;
+    while !used.insert(candidate.clone()) {
+        candidate = valid_ident(format!("{base}{n}"));
+        n += 1;
+    }
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unique_names() {
+        let mut used = HashSet::new();
+        let mut name = |s: &str| unique(&mut used, field_name(s));
+        assert_eq!(name("hp"), "hp");
+        assert_eq!(name("HP"), "hp2");
+        assert_eq!(name("hp"), "hp3");
+        assert_eq!(name("type"), "r#type");
+        assert_eq!(name("Type"), "type2");
+        assert_eq!(name("self"), "self_");
+        assert_eq!(name("Self"), "self_2");
+    }
+
+    // game_1-1-3.ldtk with entities and enums whose identifiers convert to
+    // the same names.
+    fn clashing_project() -> crate::Project {
+        let project = crate::Project::from_slice(include_bytes!("../assets/game_1-1-3.ldtk"));
+        let mut json = serde_json::to_value(project).unwrap();
+        let defs = &mut json["defs"];
+        for (uid, id) in [(900, "my_item"), (901, "MyItem")] {
+            let value = |id: &str| serde_json::json!({ "id": id, "color": 0 });
+            let def = serde_json::json!({
+                "identifier": id,
+                "uid": uid,
+                "tags": [],
+                "values": [value("sword"), value("Sword")],
+            });
+            defs["enums"].as_array_mut().unwrap().push(def);
+        }
+        // the only entity with a field
+        let bird = defs["entities"][0].clone();
+        for (uid, id, item) in [(910, "my_door", "my_item"), (911, "MyDoor", "MyItem")] {
+            let mut def = bird.clone();
+            def["identifier"] = id.into();
+            def["uid"] = uid.into();
+            let mut field = def["fieldDefs"][0].clone();
+            field["identifier"] = "key".into();
+            field["__type"] = format!("Array<LocalEnum.{item}>").into();
+            field["type"] = "F_Enum".into();
+            def["fieldDefs"] = serde_json::json!([field]);
+            defs["entities"].as_array_mut().unwrap().push(def);
+        }
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn unique_type_names() {
+        let code = CodeGenerator::new(&clashing_project().defs).generate();
+        for line in [
+            "pub enum MyItem {",
+            "pub enum MyItem2 {",
+            "Sword2,",
+            "pub struct MyDoor {",
+            "pub struct MyDoor2 {",
+            "pub key: Vec<super::enums::MyItem>,",
+            "pub key: Vec<super::enums::MyItem2>,",
+        ] {
+            assert!(code.contains(line), "no `{line}` in\n{code}");
+        }
+    }
+
+    // Compile the code generated for every bundled project, and for the
+    // clashing names above, as a crate of its own.
+    #[test]
+    fn generated_code_compiles() {
+        let dir = std::env::temp_dir().join("ldtk_rust_codegen");
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut projects: Vec<(String, crate::Project)> = std::fs::read_dir("assets")
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .filter(|p| p.extension().is_some_and(|e| e == "ldtk"))
+            .map(|p| {
+                let name = p.file_stem().unwrap().to_string_lossy().replace('-', "_");
+                (name, crate::Project::load_project(&p))
+            })
+            .collect();
+        projects.push(("clashing".to_string(), clashing_project()));
+
+        let t = trybuild::TestCases::new();
+        for (name, project) in projects {
+            let code = CodeGenerator::new(&project.defs).generate();
+            let path = dir.join(format!("codegen_{}.rs", name.to_lowercase()));
+            std::fs::write(&path, format!("mod ldtk {{\n{code}}}\n\nfn main() {{}}\n")).unwrap();
+            t.pass(path);
+        }
+    }
+}
@@ -0,0 +1,265 @@
+// Colors. LDtk writes most of them as "#rrggbb" strings, enum values keep
+// theirs as a 0xrrggbb integer. The accessors below parse the color
+// fields of the model into a Color.
+
+use crate::{
+    AutoLayerRuleGroup, EntityDefinition, EntityInstance, EnumValueDefinition, FieldDefinition,
+    IntGridValueDefinition, IntGridValueGroupDefinition, LayerDefinition, Level, Project,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use std::fmt;
+
+/// An RGBA color with 8 bits per channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b, a: 255 }
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Color { r, g, b, a }
+    }
+
+    /// Parse `#rgb`, `#rrggbb` or `#rrggbbaa` (the `#` is optional).
+    /// Returns None for anything else.
+    pub fn from_hex(s: &str) -> Option<Self> {
+        let hex = s.strip_prefix('#').unwrap_or(s);
+        if !matches!(hex.len(), 3 | 6 | 8) || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        if hex.len() == 3 {
+            // Each digit is doubled, #f80 is #ff8800
+            let channel = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok().map(|c| c * 17);
+            return Some(Color::rgb(channel(0)?, channel(1)?, channel(2)?));
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        let a = if hex.len() == 8 { channel(6)? } else { 255 };
+        Some(Color::rgba(channel(0)?, channel(2)?, channel(4)?, a))
+    }
+
+    /// A `0xrrggbb` integer, as used by enum values. Higher bits are
+    /// ignored and the color is opaque.
+    pub fn from_int(rgb: i64) -> Self {
+        Color::rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)
+    }
+
+    /// The color as a `0xrrggbb` integer, without the alpha.
+    pub fn to_int(self) -> i64 {
+        (self.r as i64) << 16 | (self.g as i64) << 8 | self.b as i64
+    }
+
+    /// `#rrggbb`, or `#rrggbbaa` if the color isn't opaque.
+    pub fn to_hex(self) -> String {
+        self.to_string()
+    }
+
+    /// The channels scaled to 0.0..=1.0, in RGBA order, as most engines
+    /// want them.
+    pub fn to_f32_array(self) -> [f32; 4] {
+        [self.r, self.g, self.b, self.a].map(|c| c as f32 / 255.0)
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)?;
+        if self.a != 255 {
+            write!(f, "{:02x}", self.a)?;
+        }
+        Ok(())
+    }
+}
+
+// Colors are (de)serialized as hex strings, like LDtk writes them.
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Color::from_hex(&s)
+            .ok_or_else(|| de::Error::invalid_value(de::Unexpected::Str(&s), &"a #rrggbb color"))
+    }
+}
+
+// The accessors return None when the color is missing or isn't a valid
+// hex string.
+
+impl Project {
+    pub fn bg_color(&self) -> Option<Color> {
+        Color::from_hex(&self.bg_color)
+    }
+
+    pub fn default_level_bg_color(&self) -> Option<Color> {
+        Color::from_hex(&self.default_level_bg_color)
+    }
+
+    /// The background color of a level, falling back to the project's
+    /// `default_level_bg_color` when the level doesn't set one.
+    pub fn level_bg_color(&self, level: &Level) -> Option<Color> {
+        match &level.level_bg_color {
+            Some(c) => Color::from_hex(c),
+            None => self.default_level_bg_color(),
+        }
+    }
+}
+
+impl Level {
+    /// The background color, with the project default already applied
+    /// by LDtk (`__bgColor`).
+    pub fn bg_color(&self) -> Option<Color> {
+        Color::from_hex(&self.bg_color)
+    }
+
+    pub fn smart_color(&self) -> Option<Color> {
+        Color::from_hex(&self.smart_color)
+    }
+
+    /// The background color set on the level itself. None means the
+    /// project default is used, see [`Project::level_bg_color`].
+    pub fn level_bg_color(&self) -> Option<Color> {
+        self.level_bg_color.as_deref().and_then(Color::from_hex)
+    }
+}
+
+impl EntityInstance {
+    pub fn smart_color(&self) -> Option<Color> {
+        Color::from_hex(&self.smart_color)
+    }
+}
+
+impl EntityDefinition {
+    pub fn color(&self) -> Option<Color> {
+        Color::from_hex(&self.color)
+    }
+}
+
+impl FieldDefinition {
+    pub fn editor_display_color(&self) -> Option<Color> {
+        self.editor_display_color
+            .as_deref()
+            .and_then(Color::from_hex)
+    }
+}
+
+impl EnumValueDefinition {
+    pub fn color(&self) -> Color {
+        Color::from_int(self.color)
+    }
+}
+
+impl LayerDefinition {
+    pub fn ui_color(&self) -> Option<Color> {
+        self.ui_color.as_deref().and_then(Color::from_hex)
+    }
+}
+
+impl AutoLayerRuleGroup {
+    pub fn color(&self) -> Option<Color> {
+        self.color.as_deref().and_then(Color::from_hex)
+    }
+}
+
+impl IntGridValueDefinition {
+    pub fn color(&self) -> Option<Color> {
+        Color::from_hex(&self.color)
+    }
+}
+
+impl IntGridValueGroupDefinition {
+    pub fn color(&self) -> Option<Color> {
+        self.color.as_deref().and_then(Color::from_hex)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_hex() {
+        assert_eq!(Color::from_hex("#f80"), Some(Color::rgb(0xff, 0x88, 0x00)));
+        assert_eq!(
+            Color::from_hex("#FFDC00"),
+            Some(Color::rgb(0xff, 0xdc, 0x00))
+        );
+        assert_eq!(
+            Color::from_hex("#12345678"),
+            Some(Color::rgba(0x12, 0x34, 0x56, 0x78))
+        );
+        // The # is optional
+        assert_eq!(Color::from_hex("ea0ccc"), Color::from_hex("#EA0CCC"));
+        assert_eq!(Color::from_hex("0f0"), Some(Color::rgb(0, 255, 0)));
+
+        for invalid in [
+            "",
+            "#",
+            "#ff",
+            "#ffff",
+            "#fffff",
+            "#fffffff",
+            "#ggg",
+            "##fff",
+            "#ffdc00 ",
+            "#ff dc 00",
+            "#ééé",
+        ] {
+            assert_eq!(Color::from_hex(invalid), None, "{invalid:?}");
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        for rgb in [0, 0xffdc00, 0xea0ccc, 0xffffff] {
+            assert_eq!(Color::from_int(rgb).to_int(), rgb);
+        }
+        // Higher bits are dropped
+        assert_eq!(Color::from_int(0x1_00ff00), Color::rgb(0, 255, 0));
+        assert_eq!(Color::rgba(1, 2, 3, 4).to_int(), 0x010203);
+
+        for color in [
+            Color::BLACK,
+            Color::rgb(0xff, 0xdc, 0x00),
+            Color::rgba(1, 2, 3, 4),
+        ] {
+            assert_eq!(Color::from_hex(&color.to_hex()), Some(color));
+            let json = serde_json::to_string(&color).unwrap();
+            assert_eq!(serde_json::from_str::<Color>(&json).unwrap(), color);
+        }
+        assert_eq!(
+            serde_json::to_string(&Color::rgb(0xff, 0xdc, 0x00)).unwrap(),
+            "\"#ffdc00\""
+        );
+        assert_eq!(
+            serde_json::to_string(&Color::rgba(1, 2, 3, 4)).unwrap(),
+            "\"#01020304\""
+        );
+        assert!(serde_json::from_str::<Color>(r#""red""#).is_err());
+        assert!(serde_json::from_str::<Color>("16768000").is_err());
+    }
+
+    #[test]
+    fn accessors() {
+        let project = Project::new("assets/SeparateLevelFiles.ldtk");
+        let level = &project.levels[0];
+        assert_eq!(level.bg_color(), Color::from_hex(&level.bg_color));
+        assert!(level.bg_color().is_some());
+        assert_eq!(
+            project.level_bg_color(level),
+            level.level_bg_color().or(project.default_level_bg_color())
+        );
+    }
+}
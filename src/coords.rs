@@ -0,0 +1,319 @@
+// Conversions between the coordinate spaces of a level, all in pixels
+// except for cells:
+//
+//   cell      a cell of a layer's grid (`__grid`, IntGrid indices)
+//   layer px  pixels from the layer's top left corner (`px` of tiles and
+//             entities)
+//   level px  pixels from the level's top left corner, the layer px moved
+//             by the layer's `__pxTotalOffsetX/Y`
+//   world px  pixels in the world, the level px moved by the level's
+//             position in its world (`worldX/worldY`, except in linear
+//             worlds where LDtk writes -1 and the levels are laid out in
+//             order, see WorldRef::level_origin)
+//
+// Forgetting the layer offsets is the classic mistake, these go through
+// every step. Pixels are floored into cells, so negative positions land
+// in negative cells. Going to or from world px needs the level's world,
+// and gives None when a level of a linear world isn't one of its levels.
+
+use crate::{EntityInstance, LayerInstance, Level, Rect, TileInstance, WorldRef};
+
+impl LayerInstance {
+    /// The layer px of a cell's top left corner.
+    pub fn cell_to_layer_px(&self, x: i64, y: i64) -> (i64, i64) {
+        (x * self.grid_size, y * self.grid_size)
+    }
+
+    /// The cell a layer px is in.
+    pub fn layer_px_to_cell(&self, x: i64, y: i64) -> (i64, i64) {
+        let size = self.grid_size.max(1);
+        (x.div_euclid(size), y.div_euclid(size))
+    }
+
+    pub fn layer_px_to_level_px(&self, x: i64, y: i64) -> (i64, i64) {
+        (x + self.px_total_offset_x, y + self.px_total_offset_y)
+    }
+
+    pub fn level_px_to_layer_px(&self, x: i64, y: i64) -> (i64, i64) {
+        (x - self.px_total_offset_x, y - self.px_total_offset_y)
+    }
+
+    /// The level px of a cell's top left corner.
+    pub fn cell_to_level_px(&self, x: i64, y: i64) -> (i64, i64) {
+        let (x, y) = self.cell_to_layer_px(x, y);
+        self.layer_px_to_level_px(x, y)
+    }
+
+    /// The cell a level px is in.
+    pub fn level_px_to_cell(&self, x: i64, y: i64) -> (i64, i64) {
+        let (x, y) = self.level_px_to_layer_px(x, y);
+        self.layer_px_to_cell(x, y)
+    }
+
+    /// The world px of a layer px, `level` being the level the layer is in
+    /// and `world` the world of the level.
+    pub fn layer_px_to_world_px(
+        &self,
+        level: &Level,
+        world: &WorldRef,
+        x: i64,
+        y: i64,
+    ) -> Option<(i64, i64)> {
+        let (x, y) = self.layer_px_to_level_px(x, y);
+        level.level_px_to_world_px(world, x, y)
+    }
+
+    pub fn world_px_to_layer_px(
+        &self,
+        level: &Level,
+        world: &WorldRef,
+        x: i64,
+        y: i64,
+    ) -> Option<(i64, i64)> {
+        let (x, y) = level.world_px_to_level_px(world, x, y)?;
+        Some(self.level_px_to_layer_px(x, y))
+    }
+
+    /// The world px of a cell's top left corner.
+    pub fn cell_to_world_px(
+        &self,
+        level: &Level,
+        world: &WorldRef,
+        x: i64,
+        y: i64,
+    ) -> Option<(i64, i64)> {
+        let (x, y) = self.cell_to_layer_px(x, y);
+        self.layer_px_to_world_px(level, world, x, y)
+    }
+
+    /// The cell a world px is in, which may be outside of the layer.
+    pub fn world_px_to_cell(
+        &self,
+        level: &Level,
+        world: &WorldRef,
+        x: i64,
+        y: i64,
+    ) -> Option<(i64, i64)> {
+        let (x, y) = self.world_px_to_layer_px(level, world, x, y)?;
+        Some(self.layer_px_to_cell(x, y))
+    }
+
+    /// The index of a cell in `int_grid_csv`, None for cells outside of
+    /// the layer.
+    pub fn cell_index(&self, x: i64, y: i64) -> Option<usize> {
+        let inside = (0..self.c_wid).contains(&x) && (0..self.c_hei).contains(&y);
+        inside.then(|| (y * self.c_wid + x) as usize)
+    }
+}
+
+impl Level {
+    /// The world px of a level px, `world` being the world of the level.
+    pub fn level_px_to_world_px(&self, world: &WorldRef, x: i64, y: i64) -> Option<(i64, i64)> {
+        let (ox, oy) = world.level_origin(self)?;
+        Some((x + ox, y + oy))
+    }
+
+    pub fn world_px_to_level_px(&self, world: &WorldRef, x: i64, y: i64) -> Option<(i64, i64)> {
+        let (ox, oy) = world.level_origin(self)?;
+        Some((x - ox, y - oy))
+    }
+}
+
+impl EntityInstance {
+    /// The position of the entity's pivot in level px.
+    pub fn level_px(&self, layer: &LayerInstance) -> (i64, i64) {
+        layer.layer_px_to_level_px(coord(&self.px, 0), coord(&self.px, 1))
+    }
+
+    /// The position of the entity's pivot in world px. Uses
+    /// `__worldX/__worldY` when LDtk wrote them (it doesn't in linear
+    /// worlds).
+    pub fn world_px(
+        &self,
+        layer: &LayerInstance,
+        level: &Level,
+        world: &WorldRef,
+    ) -> Option<(i64, i64)> {
+        if let (Some(x), Some(y)) = (self.world_x, self.world_y) {
+            return Some((x, y));
+        }
+        layer.layer_px_to_world_px(level, world, coord(&self.px, 0), coord(&self.px, 1))
+    }
+
+    /// The entity's bounding box in level px, placed around its pivot.
+    pub fn level_bounds(&self, layer: &LayerInstance) -> Rect {
+        let (x, y) = self.level_px(layer);
+        self.bounds_around(x, y)
+    }
+
+    /// The entity's bounding box in world px, placed around its pivot.
+    pub fn world_bounds(
+        &self,
+        layer: &LayerInstance,
+        level: &Level,
+        world: &WorldRef,
+    ) -> Option<Rect> {
+        let (x, y) = self.world_px(layer, level, world)?;
+        Some(self.bounds_around(x, y))
+    }
+
+    // The bounding box for the pivot at x, y.
+    fn bounds_around(&self, x: i64, y: i64) -> Rect {
+        let pivot = |i: usize| self.pivot.get(i).copied().unwrap_or(0.0);
+        let left = x - (pivot(0) * self.width as f64).round() as i64;
+        let top = y - (pivot(1) * self.height as f64).round() as i64;
+        Rect::new(left, top, self.width, self.height)
+    }
+}
+
+impl TileInstance {
+    /// The cell of the tile, `layer` being the layer it is in.
+    pub fn cell(&self, layer: &LayerInstance) -> (i64, i64) {
+        layer.layer_px_to_cell(coord(&self.px, 0), coord(&self.px, 1))
+    }
+
+    /// The tile's top left corner in level px.
+    pub fn level_px(&self, layer: &LayerInstance) -> (i64, i64) {
+        layer.layer_px_to_level_px(coord(&self.px, 0), coord(&self.px, 1))
+    }
+
+    /// The tile's top left corner in world px.
+    pub fn world_px(
+        &self,
+        layer: &LayerInstance,
+        level: &Level,
+        world: &WorldRef,
+    ) -> Option<(i64, i64)> {
+        layer.layer_px_to_world_px(level, world, coord(&self.px, 0), coord(&self.px, 1))
+    }
+
+    /// The area the tile covers in world px.
+    pub fn world_bounds(
+        &self,
+        layer: &LayerInstance,
+        level: &Level,
+        world: &WorldRef,
+    ) -> Option<Rect> {
+        let (x, y) = self.world_px(layer, level, world)?;
+        Some(Rect::new(x, y, layer.grid_size, layer.grid_size))
+    }
+}
+
+// One coordinate of an `[x, y]` array, 0 if it's missing.
+fn coord(v: &[i64], i: usize) -> i64 {
+    v.get(i).copied().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Project;
+
+    // game_1-1-3.ldtk: a LinearHorizontal world with a single level.
+    fn game() -> Project {
+        Project::from_slice(include_bytes!("../assets/game_1-1-3.ldtk"))
+    }
+
+    #[test]
+    fn linear_worlds_place_levels_in_order() {
+        // LinearVertical, Level_A is 224 pixels high
+        let project = Project::new("assets/SeparateLevelFiles.ldtk");
+        let world = project.iter_worlds().next().unwrap();
+        let b = project.get_level_by_identifier("Level_B").unwrap();
+        assert_eq!((b.world_x, b.world_y), (-1, -1));
+        assert_eq!(b.level_px_to_world_px(&world, 5, 6), Some((5, 230)));
+        assert_eq!(b.world_px_to_level_px(&world, 5, 230), Some((5, 6)));
+
+        let layer = &b.layer_instances.as_ref().unwrap()[0];
+        assert_eq!(layer.cell_to_world_px(b, &world, 1, 1), Some((16, 240)));
+        assert_eq!(layer.world_px_to_cell(b, &world, 16, 240), Some((1, 1)));
+
+        // a level of another world can't be placed in this one
+        let game = game();
+        let other = &game.levels[0];
+        assert_eq!(other.level_px_to_world_px(&world, 5, 6), None);
+    }
+
+    #[test]
+    fn entities_of_linear_worlds() {
+        let project = game();
+        let world = project.iter_worlds().next().unwrap();
+        let level = &project.levels[0];
+        let layer = &level.layer_instances.as_ref().unwrap()[0];
+        let hero = layer
+            .entity_instances
+            .iter()
+            .find(|e| e.identifier == "Hero")
+            .unwrap();
+        // LDtk doesn't write __worldX/Y here, and the level is at -1, -1
+        assert_eq!(hero.world_x, None);
+        assert_eq!(hero.world_px(layer, level, &world), Some((80, 8)));
+        assert_eq!(
+            hero.world_bounds(layer, level, &world),
+            Some(Rect::new(80, 8, 8, 8))
+        );
+
+        let mut placed = hero.clone();
+        placed.world_x = Some(1000);
+        placed.world_y = Some(2000);
+        assert_eq!(placed.world_px(layer, level, &world), Some((1000, 2000)));
+    }
+
+    #[test]
+    fn round_trips_with_layer_offsets() {
+        let project = game();
+        let world = project.iter_worlds().next().unwrap();
+        let level = &project.levels[0];
+        let mut layer = level.layer_instances.as_ref().unwrap()[0].clone();
+        layer.px_total_offset_x = 5;
+        layer.px_total_offset_y = -3;
+        assert_eq!(layer.grid_size, 8);
+
+        assert_eq!(layer.cell_to_level_px(1, 2), (13, 13));
+        assert_eq!(layer.level_px_to_cell(13, 13), (1, 2));
+        // the offset moves the cells, (0, 0) is now in the last cell
+        // before the layer
+        assert_eq!(layer.level_px_to_cell(0, 0), (-1, 0));
+        for y in -3..3 {
+            for x in -3..3 {
+                let (px, py) = layer.cell_to_layer_px(x, y);
+                assert_eq!(layer.layer_px_to_cell(px + 7, py + 7), (x, y));
+                let (lx, ly) = layer.layer_px_to_level_px(px, py);
+                assert_eq!(layer.level_px_to_layer_px(lx, ly), (px, py));
+                let (wx, wy) = layer.cell_to_world_px(level, &world, x, y).unwrap();
+                assert_eq!(layer.world_px_to_cell(level, &world, wx, wy), Some((x, y)));
+                assert_eq!(
+                    layer.world_px_to_layer_px(level, &world, wx, wy),
+                    Some((px, py))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn bounds_around_the_pivot() {
+        let project = game();
+        let world = project.iter_worlds().next().unwrap();
+        let level = &project.levels[0];
+        let mut layer = level.layer_instances.as_ref().unwrap()[0].clone();
+        layer.px_total_offset_x = 5;
+        layer.px_total_offset_y = -3;
+        let mut entity = layer.entity_instances[0].clone();
+        entity.px = vec![80, 8];
+        entity.width = 16;
+        entity.height = 8;
+
+        entity.pivot = vec![0.0, 0.0];
+        assert_eq!(entity.level_bounds(&layer), Rect::new(85, 5, 16, 8));
+        // bottom center
+        entity.pivot = vec![0.5, 1.0];
+        assert_eq!(entity.level_px(&layer), (85, 5));
+        assert_eq!(entity.level_bounds(&layer), Rect::new(77, -3, 16, 8));
+        assert_eq!(
+            entity.world_bounds(&layer, level, &world),
+            Some(Rect::new(77, -3, 16, 8))
+        );
+        entity.pivot = vec![1.0, 0.5];
+        assert_eq!(entity.level_bounds(&layer), Rect::new(69, 1, 16, 8));
+    }
+}
@@ -0,0 +1,238 @@
+// A serde Deserializer over a list of field instances. The fields are
+// presented as a map from identifier to value, so a user struct deriving
+// Deserialize can be filled straight from an entity's or a level's fields.
+// Null fields are left out of the map: they become None for Option fields
+// and the default for fields marked #[serde(default)].
+
+use crate::{FieldError, FieldInstance};
+use serde::de::{
+    self, DeserializeSeed, Deserializer, MapAccess, Visitor, value::BorrowedStrDeserializer,
+};
+use std::{fmt, slice};
+
+/// Fill a `T` from custom fields, using their identifiers as keys.
+///
+/// ```ignore
+/// #[derive(Deserialize)]
+/// struct Door {
+///     target: EntityRef,
+///     #[serde(rename = "isLocked", default)]
+///     locked: bool,
+///     key: Option<String>,
+/// }
+///
+/// let door: Door = ldtk_rust::from_fields(&entity.field_instances)?;
+/// ```
+pub fn from_fields<'de, T: de::Deserialize<'de>>(
+    fields: &'de [FieldInstance],
+) -> Result<T, FieldError> {
+    T::deserialize(FieldsDeserializer::new(fields)).map_err(|e| match e {
+        // a field that is there but null was skipped, say so
+        FieldError::Missing { field } if fields.iter().any(|f| f.identifier == field) => {
+            FieldError::Null { field, index: None }
+        }
+        e => e,
+    })
+}
+
+/// Deserializer behind [`from_fields`].
+pub struct FieldsDeserializer<'de> {
+    fields: &'de [FieldInstance],
+}
+
+impl<'de> FieldsDeserializer<'de> {
+    pub fn new(fields: &'de [FieldInstance]) -> Self {
+        FieldsDeserializer { fields }
+    }
+}
+
+impl<'de> Deserializer<'de> for FieldsDeserializer<'de> {
+    type Error = FieldError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, FieldError> {
+        visitor.visit_map(FieldsMap {
+            fields: self.fields.iter(),
+            current: None,
+        })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct FieldsMap<'de> {
+    fields: slice::Iter<'de, FieldInstance>,
+    // the field whose key was handed out last
+    current: Option<&'de FieldInstance>,
+}
+
+impl<'de> MapAccess<'de> for FieldsMap<'de> {
+    type Error = FieldError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, FieldError> {
+        let next = self
+            .fields
+            .find(|f| f.value.as_ref().is_some_and(|v| !v.is_null()));
+        self.current = next;
+        next.map(|f| seed.deserialize(BorrowedStrDeserializer::new(&f.identifier)))
+            .transpose()
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, FieldError> {
+        let field = self
+            .current
+            .take()
+            .ok_or_else(|| de::Error::custom("value asked for before its key"))?;
+        let value = field.value.as_ref().unwrap_or(&serde_json::Value::Null);
+        seed.deserialize(value)
+            .map_err(|e| FieldError::Deserialize {
+                field: Some(field.identifier.clone()),
+                message: e.to_string(),
+            })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.fields.len())
+    }
+}
+
+impl de::Error for FieldError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        FieldError::Deserialize {
+            field: None,
+            message: msg.to_string(),
+        }
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        FieldError::Missing {
+            field: field.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Color, EntityRef, FieldAccess, Project};
+    use serde::Deserialize;
+    use serde_json::json;
+
+    fn levels() -> Project {
+        Project::new("assets/SeparateLevelFiles.ldtk")
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct LevelFields {
+        tutorial: Option<String>,
+        #[serde(rename = "textColor")]
+        text_color: Color,
+    }
+
+    #[test]
+    fn options_and_renames() {
+        let project = levels();
+        let a: LevelFields = project.levels[0].fields_as().unwrap();
+        assert!(a.tutorial.unwrap().starts_with("In this example"));
+        assert_eq!(a.text_color, Color::rgb(0xff, 0xdc, 0x00));
+        // Level_B has no tutorial
+        let b: LevelFields = from_fields(&project.levels[1].field_instances).unwrap();
+        assert_eq!(b.tutorial, None);
+    }
+
+    #[test]
+    fn defaults_for_null_and_missing_fields() {
+        #[derive(Deserialize)]
+        struct Defaults {
+            #[serde(default)]
+            tutorial: String,
+            #[serde(default)]
+            music: Option<String>,
+        }
+        let project = levels();
+        let b: Defaults = project.levels[1].fields_as().unwrap();
+        assert_eq!(b.tutorial, "");
+        assert_eq!(b.music, None);
+    }
+
+    #[test]
+    fn errors() {
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Required {
+            tutorial: String,
+        }
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Music {
+            music: String,
+        }
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct WrongType {
+            #[serde(rename = "textColor")]
+            text_color: i64,
+        }
+        let project = levels();
+        let b = &project.levels[1];
+        assert!(matches!(
+            b.fields_as::<Required>(),
+            Err(FieldError::Null { field, index: None }) if field == "tutorial"
+        ));
+        assert!(matches!(
+            b.fields_as::<Music>(),
+            Err(FieldError::Missing { field }) if field == "music"
+        ));
+        assert!(matches!(
+            b.fields_as::<WrongType>(),
+            Err(FieldError::Deserialize { field: Some(field), .. }) if field == "textColor"
+        ));
+    }
+
+    #[test]
+    fn structured_values() {
+        #[derive(Deserialize)]
+        struct Door {
+            target: EntityRef,
+            #[serde(rename = "isLocked", default)]
+            locked: bool,
+            keys: Vec<Option<String>>,
+        }
+        let field = |identifier: &str, field_type: &str, value| FieldInstance {
+            identifier: identifier.to_string(),
+            tile: None,
+            field_instance_type: field_type.to_string(),
+            value: Some(value),
+            def_uid: 0,
+            real_editor_values: Vec::new(),
+        };
+        let fields = [
+            field(
+                "target",
+                "EntityRef",
+                json!({
+                    "entityIid": "e",
+                    "layerIid": "l",
+                    "levelIid": "v",
+                    "worldIid": "w",
+                }),
+            ),
+            field("isLocked", "Bool", serde_json::Value::Null),
+            field("keys", "Array<LocalEnum.Key>", json!(["Red", null])),
+            field("unused", "Int", json!(1)),
+        ];
+        let door: Door = from_fields(&fields).unwrap();
+        assert_eq!(door.target.entity_iid, "e");
+        assert!(!door.locked);
+        assert_eq!(door.keys, [Some("Red".to_string()), None]);
+    }
+}
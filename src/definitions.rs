@@ -0,0 +1,92 @@
+// Lookups from an instance to its definition. Instances only keep the uid
+// of their definition (def_uid, layer_def_uid, ...), these follow it into
+// the project's `defs`. They scan the definitions, a ProjectIndex finds
+// them by uid in constant time instead.
+
+use crate::{
+    EntityDefinition, EntityInstance, EnumDefinition, FieldDefinition, FieldInstance,
+    IntGridValueDefinition, IntGridValueInstance, LayerDefinition, LayerInstance, Project,
+    TilesetDefinition,
+};
+
+impl Project {
+    pub fn get_entity_def(&self, uid: i64) -> Option<&EntityDefinition> {
+        self.defs.entities.iter().find(|d| d.uid == uid)
+    }
+
+    pub fn get_layer_def(&self, uid: i64) -> Option<&LayerDefinition> {
+        self.defs.layers.iter().find(|d| d.uid == uid)
+    }
+
+    pub fn get_tileset_def(&self, uid: i64) -> Option<&TilesetDefinition> {
+        self.defs.tilesets.iter().find(|d| d.uid == uid)
+    }
+
+    /// Local and external enums alike.
+    pub fn get_enum_def(&self, uid: i64) -> Option<&EnumDefinition> {
+        self.defs
+            .enums
+            .iter()
+            .chain(&self.defs.external_enums)
+            .find(|d| d.uid == uid)
+    }
+
+    /// Entity and level fields alike.
+    pub fn get_field_def(&self, uid: i64) -> Option<&FieldDefinition> {
+        let defs = &self.defs;
+        defs.entities
+            .iter()
+            .flat_map(|e| &e.field_defs)
+            .chain(&defs.level_fields)
+            .find(|d| d.uid == uid)
+    }
+}
+
+impl LayerInstance {
+    pub fn definition<'a>(&self, project: &'a Project) -> Option<&'a LayerDefinition> {
+        project.get_layer_def(self.layer_def_uid)
+    }
+
+    /// The tileset the layer's tiles come from: the layer's override if it
+    /// has one, the tileset of its definition otherwise.
+    pub fn tileset_definition<'a>(&self, project: &'a Project) -> Option<&'a TilesetDefinition> {
+        let uid = self.override_tileset_uid.or(self.tileset_def_uid)?;
+        project.get_tileset_def(uid)
+    }
+
+    /// The definition of an IntGrid value of this layer, as found in
+    /// `int_grid_csv`. None for 0 (empty cells).
+    pub fn int_grid_value_definition<'a>(
+        &self,
+        project: &'a Project,
+        value: i64,
+    ) -> Option<&'a IntGridValueDefinition> {
+        self.definition(project)?
+            .int_grid_values
+            .iter()
+            .find(|d| d.value == value)
+    }
+}
+
+impl EntityInstance {
+    pub fn definition<'a>(&self, project: &'a Project) -> Option<&'a EntityDefinition> {
+        project.get_entity_def(self.def_uid)
+    }
+}
+
+impl FieldInstance {
+    pub fn definition<'a>(&self, project: &'a Project) -> Option<&'a FieldDefinition> {
+        project.get_field_def(self.def_uid)
+    }
+}
+
+impl IntGridValueInstance {
+    /// The definition of the value, `layer` being the layer it is in.
+    pub fn definition<'a>(
+        &self,
+        layer: &LayerInstance,
+        project: &'a Project,
+    ) -> Option<&'a IntGridValueDefinition> {
+        layer.int_grid_value_definition(project, self.v)
+    }
+}
@@ -0,0 +1,220 @@
+// Error types. LdtkError is returned by the fallible loading API; the
+// panicking constructors (Project::new and friends) are thin wrappers
+// around the try_* versions and panic with the Display output of these
+// errors. FieldError is returned when reading custom field values.
+
+use crate::FieldType;
+use std::{error::Error, fmt, io, path::PathBuf};
+
+/// Everything that can go wrong while loading LDtk data.
+#[derive(Debug)]
+pub enum LdtkError {
+    /// The file could not be opened or read.
+    Io {
+        path: Option<PathBuf>,
+        source: io::Error,
+    },
+
+    /// The file is not valid JSON (this includes truncated files).
+    Syntax {
+        path: Option<PathBuf>,
+        line: usize,
+        column: usize,
+        source: serde_json::Error,
+    },
+
+    /// The file is valid JSON but does not match the LDtk schema, usually
+    /// because it was saved by a different LDtk version.
+    Schema {
+        path: Option<PathBuf>,
+        line: usize,
+        column: usize,
+        source: serde_json::Error,
+    },
+
+    /// A level is stored in an external file that has no path or that
+    /// could not be found.
+    MissingExternalLevel {
+        identifier: String,
+        path: Option<PathBuf>,
+    },
+
+    /// A path had to be converted to a string but is not valid UTF-8.
+    NonUtf8Path(PathBuf),
+}
+
+impl LdtkError {
+    // Sort a serde_json error into the IO/syntax/schema buckets, keeping the
+    // location reported by serde.
+    pub(crate) fn from_json(source: serde_json::Error, path: Option<PathBuf>) -> Self {
+        let line = source.line();
+        let column = source.column();
+        match source.classify() {
+            serde_json::error::Category::Io => LdtkError::Io {
+                path,
+                source: source.into(),
+            },
+            serde_json::error::Category::Syntax | serde_json::error::Category::Eof => {
+                LdtkError::Syntax {
+                    path,
+                    line,
+                    column,
+                    source,
+                }
+            }
+            serde_json::error::Category::Data => LdtkError::Schema {
+                path,
+                line,
+                column,
+                source,
+            },
+        }
+    }
+
+    pub(crate) fn from_io(source: io::Error, path: Option<PathBuf>) -> Self {
+        LdtkError::Io { path, source }
+    }
+
+    /// The file this error relates to, if known.
+    pub fn path(&self) -> Option<&PathBuf> {
+        match self {
+            LdtkError::Io { path, .. }
+            | LdtkError::Syntax { path, .. }
+            | LdtkError::Schema { path, .. }
+            | LdtkError::MissingExternalLevel { path, .. } => path.as_ref(),
+            LdtkError::NonUtf8Path(path) => Some(path),
+        }
+    }
+}
+
+// "in <path>" suffix shared by the Display impl
+struct InPath<'a>(&'a Option<PathBuf>);
+
+impl fmt::Display for InPath<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(p) => write!(f, " in {}", p.display()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for LdtkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LdtkError::Io { path, source } => {
+                write!(f, "io error{}: {}", InPath(path), source)
+            }
+            // serde_json already appends the line and column
+            LdtkError::Syntax { path, source, .. } => {
+                write!(f, "invalid JSON{}: {}", InPath(path), source)
+            }
+            LdtkError::Schema { path, source, .. } => {
+                write!(
+                    f,
+                    "JSON does not match the LDtk schema{}: {}",
+                    InPath(path),
+                    source
+                )
+            }
+            LdtkError::MissingExternalLevel { identifier, path } => match path {
+                Some(p) => write!(
+                    f,
+                    "external level file for {} not found at {}",
+                    identifier,
+                    p.display()
+                ),
+                None => write!(f, "level {} has no external file path", identifier),
+            },
+            LdtkError::NonUtf8Path(p) => write!(f, "path is not valid UTF-8: {}", p.display()),
+        }
+    }
+}
+
+impl Error for LdtkError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LdtkError::Io { source, .. } => Some(source),
+            LdtkError::Syntax { source, .. } | LdtkError::Schema { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// A custom field value that couldn't be read.
+#[derive(Debug)]
+pub enum FieldError {
+    /// There is no field with this identifier.
+    Missing { field: String },
+
+    /// The field is not of the type that was asked for.
+    WrongType {
+        field: String,
+        expected: &'static str,
+        found: FieldType,
+    },
+
+    /// The field is null, or for array fields the element at `index` is.
+    Null { field: String, index: Option<usize> },
+
+    /// The field's `__type` isn't one LDtk writes.
+    UnknownType { field: String, field_type: String },
+
+    /// The field's `__value` doesn't match its `__type`.
+    InvalidValue {
+        field: String,
+        field_type: FieldType,
+        value: serde_json::Value,
+    },
+
+    /// Fields couldn't be deserialized into a user type. `field` is the
+    /// field that failed, if known.
+    Deserialize {
+        field: Option<String>,
+        message: String,
+    },
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldError::Missing { field } => write!(f, "no field named {}", field),
+            FieldError::WrongType {
+                field,
+                expected,
+                found,
+            } => write!(
+                f,
+                "field {} has type {}, expected {}",
+                field, found, expected
+            ),
+            FieldError::Null { field, index: None } => write!(f, "field {} is null", field),
+            FieldError::Null {
+                field,
+                index: Some(i),
+            } => write!(f, "element {} of field {} is null", i, field),
+            FieldError::UnknownType { field, field_type } => {
+                write!(f, "field {} has unknown type {}", field, field_type)
+            }
+            FieldError::InvalidValue {
+                field,
+                field_type,
+                value,
+            } => write!(
+                f,
+                "field {} has value {} which is not a valid {}",
+                field, value, field_type
+            ),
+            FieldError::Deserialize {
+                field: Some(field),
+                message,
+            } => write!(f, "field {}: {}", field, message),
+            FieldError::Deserialize {
+                field: None,
+                message,
+            } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for FieldError {}
@@ -0,0 +1,716 @@
+// Typed access to the values of custom fields. LDtk stores a field's value
+// as plain JSON and describes it with the `__type` string, this turns the
+// pair into a FieldValue.
+
+use crate::{
+    Color, EntityInstance, FieldError, FieldInstance, GridPoint, Level,
+    ReferenceToAnEntityInstance, TilesetRectangle, from_fields,
+};
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::Value;
+use std::fmt;
+
+/// The value of an `EntityRef` field.
+pub type EntityRef = ReferenceToAnEntityInstance;
+
+/// The type of a custom field, parsed from a `__type` string such as
+/// `Int`, `LocalEnum.Item` or `Array<Point>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FieldType {
+    Int,
+    Float,
+    Bool,
+    String,
+    Multilines,
+    Color,
+    Point,
+    EntityRef,
+    Tile,
+    FilePath,
+    /// An enum defined in the project, with the name of the enum.
+    LocalEnum(String),
+    /// An enum imported from an external file, with the name of the enum.
+    ExternEnum(String),
+    Array(Box<FieldType>),
+}
+
+impl FieldType {
+    /// Parse a `__type` string. Returns None for types LDtk doesn't write.
+    pub fn parse(s: &str) -> Option<FieldType> {
+        if let Some(item) = s.strip_prefix("Array<").and_then(|s| s.strip_suffix('>')) {
+            return FieldType::parse(item).map(|t| FieldType::Array(Box::new(t)));
+        }
+        if let Some(name) = s.strip_prefix("LocalEnum.") {
+            return Some(FieldType::LocalEnum(name.to_string()));
+        }
+        if let Some(name) = s.strip_prefix("ExternEnum.") {
+            return Some(FieldType::ExternEnum(name.to_string()));
+        }
+        Some(match s {
+            "Int" => FieldType::Int,
+            "Float" => FieldType::Float,
+            "Bool" => FieldType::Bool,
+            "String" => FieldType::String,
+            "Multilines" => FieldType::Multilines,
+            "Color" => FieldType::Color,
+            "Point" => FieldType::Point,
+            "EntityRef" => FieldType::EntityRef,
+            "Tile" => FieldType::Tile,
+            "FilePath" => FieldType::FilePath,
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldType::Int => write!(f, "Int"),
+            FieldType::Float => write!(f, "Float"),
+            FieldType::Bool => write!(f, "Bool"),
+            FieldType::String => write!(f, "String"),
+            FieldType::Multilines => write!(f, "Multilines"),
+            FieldType::Color => write!(f, "Color"),
+            FieldType::Point => write!(f, "Point"),
+            FieldType::EntityRef => write!(f, "EntityRef"),
+            FieldType::Tile => write!(f, "Tile"),
+            FieldType::FilePath => write!(f, "FilePath"),
+            FieldType::LocalEnum(name) => write!(f, "LocalEnum.{name}"),
+            FieldType::ExternEnum(name) => write!(f, "ExternEnum.{name}"),
+            FieldType::Array(item) => write!(f, "Array<{item}>"),
+        }
+    }
+}
+
+/// The value of a custom field, typed according to its `__type`.
+#[derive(Debug, Clone)]
+pub enum FieldValue {
+    /// The field (or an element of an array field) has no value.
+    Null,
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    String(String),
+    Multilines(String),
+    Color(Color),
+    Point(GridPoint),
+    EntityRef(ReferenceToAnEntityInstance),
+    Tile(TilesetRectangle),
+    FilePath(String),
+    LocalEnum {
+        name: String,
+        value: String,
+    },
+    ExternEnum {
+        name: String,
+        value: String,
+    },
+    Array(Vec<FieldValue>),
+}
+
+impl FieldValue {
+    /// Convert a JSON value of the given field type.
+    pub fn from_json(field_type: &FieldType, value: &Value) -> Option<FieldValue> {
+        if value.is_null() {
+            return Some(FieldValue::Null);
+        }
+        let string = || value.as_str().map(str::to_string);
+        Some(match field_type {
+            FieldType::Int => FieldValue::Int(value.as_i64()?),
+            FieldType::Float => FieldValue::Float(value.as_f64()?),
+            FieldType::Bool => FieldValue::Bool(value.as_bool()?),
+            FieldType::String => FieldValue::String(string()?),
+            FieldType::Multilines => FieldValue::Multilines(string()?),
+            FieldType::Color => FieldValue::Color(Color::from_hex(value.as_str()?)?),
+            FieldType::Point => FieldValue::Point(object(value)?),
+            FieldType::EntityRef => FieldValue::EntityRef(object(value)?),
+            FieldType::Tile => FieldValue::Tile(object(value)?),
+            FieldType::FilePath => FieldValue::FilePath(string()?),
+            FieldType::LocalEnum(name) => FieldValue::LocalEnum {
+                name: name.clone(),
+                value: string()?,
+            },
+            FieldType::ExternEnum(name) => FieldValue::ExternEnum {
+                name: name.clone(),
+                value: string()?,
+            },
+            FieldType::Array(item) => FieldValue::Array(
+                value
+                    .as_array()?
+                    .iter()
+                    .map(|v| FieldValue::from_json(item, v))
+                    .collect::<Option<_>>()?,
+            ),
+        })
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, FieldValue::Null)
+    }
+}
+
+fn object<T: DeserializeOwned>(value: &Value) -> Option<T> {
+    T::deserialize(value).ok()
+}
+
+impl FieldInstance {
+    /// The field's `__type`, parsed.
+    pub fn field_type(&self) -> Result<FieldType, FieldError> {
+        FieldType::parse(&self.field_instance_type).ok_or_else(|| FieldError::UnknownType {
+            field: self.identifier.clone(),
+            field_type: self.field_instance_type.clone(),
+        })
+    }
+
+    /// The field's `__value`, typed according to its `__type`.
+    pub fn typed_value(&self) -> Result<FieldValue, FieldError> {
+        let field_type = self.field_type()?;
+        let value = self.value.as_ref().unwrap_or(&Value::Null);
+        FieldValue::from_json(&field_type, value).ok_or_else(|| FieldError::InvalidValue {
+            field: self.identifier.clone(),
+            field_type,
+            value: value.clone(),
+        })
+    }
+}
+
+/// Look up custom fields by identifier and read them as a given type.
+///
+/// Implemented by [`EntityInstance`] and [`Level`], and by a plain slice
+/// of field instances. The `get_*` methods fail with a [`FieldError`] if
+/// the field doesn't exist, has another type or is null. Enum getters
+/// return the name of the enum value.
+pub trait FieldAccess {
+    fn field_instances(&self) -> &[FieldInstance];
+
+    fn field(&self, identifier: &str) -> Option<&FieldInstance> {
+        self.field_instances()
+            .iter()
+            .find(|f| f.identifier == identifier)
+    }
+
+    /// The typed value of a field, which may be [`FieldValue::Null`].
+    fn field_value(&self, identifier: &str) -> Result<FieldValue, FieldError> {
+        self.field(identifier)
+            .ok_or_else(|| FieldError::Missing {
+                field: identifier.to_string(),
+            })?
+            .typed_value()
+    }
+
+    /// Deserialize all the fields into a `T`, see [`from_fields`].
+    ///
+    /// [`from_fields`]: crate::from_fields
+    fn fields_as<'de, T: Deserialize<'de>>(&'de self) -> Result<T, FieldError> {
+        from_fields(self.field_instances())
+    }
+
+    fn get_int(&self, identifier: &str) -> Result<i64, FieldError> {
+        get(self, identifier, "Int", as_int)
+    }
+
+    fn get_float(&self, identifier: &str) -> Result<f64, FieldError> {
+        get(self, identifier, "Float", as_float)
+    }
+
+    fn get_bool(&self, identifier: &str) -> Result<bool, FieldError> {
+        get(self, identifier, "Bool", as_bool)
+    }
+
+    /// A `String` or `Multilines` field.
+    fn get_string(&self, identifier: &str) -> Result<String, FieldError> {
+        get(self, identifier, "String", as_string)
+    }
+
+    fn get_color(&self, identifier: &str) -> Result<Color, FieldError> {
+        get(self, identifier, "Color", as_color)
+    }
+
+    fn get_point(&self, identifier: &str) -> Result<GridPoint, FieldError> {
+        get(self, identifier, "Point", as_point)
+    }
+
+    fn get_entity_ref(&self, identifier: &str) -> Result<ReferenceToAnEntityInstance, FieldError> {
+        get(self, identifier, "EntityRef", as_entity_ref)
+    }
+
+    fn get_tile(&self, identifier: &str) -> Result<TilesetRectangle, FieldError> {
+        get(self, identifier, "Tile", as_tile)
+    }
+
+    fn get_file_path(&self, identifier: &str) -> Result<String, FieldError> {
+        get(self, identifier, "FilePath", as_file_path)
+    }
+
+    /// A `LocalEnum` or `ExternEnum` field.
+    fn get_enum(&self, identifier: &str) -> Result<String, FieldError> {
+        get(self, identifier, "LocalEnum or ExternEnum", as_enum)
+    }
+
+    fn get_int_array(&self, identifier: &str) -> Result<Vec<i64>, FieldError> {
+        get_array(self, identifier, "Array<Int>", as_int)
+    }
+
+    fn get_float_array(&self, identifier: &str) -> Result<Vec<f64>, FieldError> {
+        get_array(self, identifier, "Array<Float>", as_float)
+    }
+
+    fn get_bool_array(&self, identifier: &str) -> Result<Vec<bool>, FieldError> {
+        get_array(self, identifier, "Array<Bool>", as_bool)
+    }
+
+    fn get_string_array(&self, identifier: &str) -> Result<Vec<String>, FieldError> {
+        get_array(self, identifier, "Array<String>", as_string)
+    }
+
+    fn get_color_array(&self, identifier: &str) -> Result<Vec<Color>, FieldError> {
+        get_array(self, identifier, "Array<Color>", as_color)
+    }
+
+    fn get_point_array(&self, identifier: &str) -> Result<Vec<GridPoint>, FieldError> {
+        get_array(self, identifier, "Array<Point>", as_point)
+    }
+
+    fn get_entity_ref_array(
+        &self,
+        identifier: &str,
+    ) -> Result<Vec<ReferenceToAnEntityInstance>, FieldError> {
+        get_array(self, identifier, "Array<EntityRef>", as_entity_ref)
+    }
+
+    fn get_tile_array(&self, identifier: &str) -> Result<Vec<TilesetRectangle>, FieldError> {
+        get_array(self, identifier, "Array<Tile>", as_tile)
+    }
+
+    fn get_file_path_array(&self, identifier: &str) -> Result<Vec<String>, FieldError> {
+        get_array(self, identifier, "Array<FilePath>", as_file_path)
+    }
+
+    fn get_enum_array(&self, identifier: &str) -> Result<Vec<String>, FieldError> {
+        get_array(self, identifier, "Array<LocalEnum or ExternEnum>", as_enum)
+    }
+}
+
+impl FieldAccess for [FieldInstance] {
+    fn field_instances(&self) -> &[FieldInstance] {
+        self
+    }
+}
+
+impl FieldAccess for EntityInstance {
+    fn field_instances(&self) -> &[FieldInstance] {
+        &self.field_instances
+    }
+}
+
+impl FieldAccess for Level {
+    fn field_instances(&self) -> &[FieldInstance] {
+        &self.field_instances
+    }
+}
+
+// Read a field with `f`, which returns None if the value is of another
+// type.
+fn get<A, T, F>(a: &A, identifier: &str, expected: &'static str, f: F) -> Result<T, FieldError>
+where
+    A: FieldAccess + ?Sized,
+    F: Fn(FieldValue) -> Option<T>,
+{
+    let (field, value) = typed_field(a, identifier)?;
+    if value.is_null() {
+        return Err(null(field, None));
+    }
+    f(value).ok_or_else(|| wrong_type(field, expected))
+}
+
+// Same as get() for array fields, `f` reading each element.
+fn get_array<A, T, F>(
+    a: &A,
+    identifier: &str,
+    expected: &'static str,
+    f: F,
+) -> Result<Vec<T>, FieldError>
+where
+    A: FieldAccess + ?Sized,
+    F: Fn(FieldValue) -> Option<T>,
+{
+    let (field, value) = typed_field(a, identifier)?;
+    let items = match value {
+        FieldValue::Array(items) => items,
+        FieldValue::Null => return Err(null(field, None)),
+        _ => return Err(wrong_type(field, expected)),
+    };
+    items
+        .into_iter()
+        .enumerate()
+        .map(|(i, item)| match item {
+            FieldValue::Null => Err(null(field, Some(i))),
+            item => f(item).ok_or_else(|| wrong_type(field, expected)),
+        })
+        .collect()
+}
+
+fn typed_field<'a, A: FieldAccess + ?Sized>(
+    a: &'a A,
+    identifier: &str,
+) -> Result<(&'a FieldInstance, FieldValue), FieldError> {
+    let field = a.field(identifier).ok_or_else(|| FieldError::Missing {
+        field: identifier.to_string(),
+    })?;
+    Ok((field, field.typed_value()?))
+}
+
+fn null(field: &FieldInstance, index: Option<usize>) -> FieldError {
+    FieldError::Null {
+        field: field.identifier.clone(),
+        index,
+    }
+}
+
+fn wrong_type(field: &FieldInstance, expected: &'static str) -> FieldError {
+    // typed_value() succeeded, so the type is known
+    match field.field_type() {
+        Ok(found) => FieldError::WrongType {
+            field: field.identifier.clone(),
+            expected,
+            found,
+        },
+        Err(e) => e,
+    }
+}
+
+fn as_int(v: FieldValue) -> Option<i64> {
+    match v {
+        FieldValue::Int(i) => Some(i),
+        _ => None,
+    }
+}
+
+fn as_float(v: FieldValue) -> Option<f64> {
+    match v {
+        FieldValue::Float(f) => Some(f),
+        _ => None,
+    }
+}
+
+fn as_bool(v: FieldValue) -> Option<bool> {
+    match v {
+        FieldValue::Bool(b) => Some(b),
+        _ => None,
+    }
+}
+
+fn as_string(v: FieldValue) -> Option<String> {
+    match v {
+        FieldValue::String(s) | FieldValue::Multilines(s) => Some(s),
+        _ => None,
+    }
+}
+
+fn as_color(v: FieldValue) -> Option<Color> {
+    match v {
+        FieldValue::Color(c) => Some(c),
+        _ => None,
+    }
+}
+
+fn as_point(v: FieldValue) -> Option<GridPoint> {
+    match v {
+        FieldValue::Point(p) => Some(p),
+        _ => None,
+    }
+}
+
+fn as_entity_ref(v: FieldValue) -> Option<ReferenceToAnEntityInstance> {
+    match v {
+        FieldValue::EntityRef(r) => Some(r),
+        _ => None,
+    }
+}
+
+fn as_tile(v: FieldValue) -> Option<TilesetRectangle> {
+    match v {
+        FieldValue::Tile(t) => Some(t),
+        _ => None,
+    }
+}
+
+fn as_file_path(v: FieldValue) -> Option<String> {
+    match v {
+        FieldValue::FilePath(p) => Some(p),
+        _ => None,
+    }
+}
+
+fn as_enum(v: FieldValue) -> Option<String> {
+    match v {
+        FieldValue::LocalEnum { value, .. } | FieldValue::ExternEnum { value, .. } => Some(value),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parse_types() {
+        for s in [
+            "Int",
+            "Float",
+            "Bool",
+            "String",
+            "Multilines",
+            "Color",
+            "Point",
+            "EntityRef",
+            "Tile",
+            "FilePath",
+            "LocalEnum.Item",
+            "ExternEnum.Music",
+            "Array<Int>",
+            "Array<LocalEnum.Item>",
+            "Array<ExternEnum.Music>",
+        ] {
+            let t = FieldType::parse(s).unwrap_or_else(|| panic!("{s} didn't parse"));
+            assert_eq!(t.to_string(), s);
+        }
+        assert_eq!(
+            FieldType::parse("Array<LocalEnum.Item>"),
+            Some(FieldType::Array(Box::new(FieldType::LocalEnum(
+                "Item".to_string()
+            ))))
+        );
+        assert_eq!(
+            FieldType::parse("ExternEnum.Music"),
+            Some(FieldType::ExternEnum("Music".to_string()))
+        );
+        for s in ["", "int", "Array<Foo>", "Array<Int", "Enum.Item"] {
+            assert_eq!(FieldType::parse(s), None, "{s}");
+        }
+    }
+
+    #[test]
+    fn values_of_every_type() {
+        let value = |t: &str, v: Value| FieldValue::from_json(&FieldType::parse(t).unwrap(), &v);
+        assert!(matches!(value("Int", json!(3)), Some(FieldValue::Int(3))));
+        assert!(matches!(
+            value("Float", json!(1.5)),
+            Some(FieldValue::Float(1.5))
+        ));
+        // LDtk writes whole floats without a fraction
+        assert!(matches!(
+            value("Float", json!(2)),
+            Some(FieldValue::Float(2.0))
+        ));
+        assert!(matches!(
+            value("Bool", json!(true)),
+            Some(FieldValue::Bool(true))
+        ));
+        assert!(matches!(value("String", json!("hi")), Some(FieldValue::String(s)) if s == "hi"));
+        assert!(matches!(
+            value("Multilines", json!("a\nb")),
+            Some(FieldValue::Multilines(s)) if s == "a\nb"
+        ));
+        assert!(matches!(
+            value("Color", json!("#FFDC00")),
+            Some(FieldValue::Color(c)) if c == Color::rgb(0xff, 0xdc, 0x00)
+        ));
+        assert!(matches!(
+            value("Point", json!({ "cx": 4, "cy": 5 })),
+            Some(FieldValue::Point(GridPoint { cx: 4, cy: 5 }))
+        ));
+        let entity_ref = json!({
+            "entityIid": "e",
+            "layerIid": "l",
+            "levelIid": "v",
+            "worldIid": "w",
+        });
+        assert!(matches!(
+            value("EntityRef", entity_ref),
+            Some(FieldValue::EntityRef(r)) if r.entity_iid == "e" && r.world_iid == "w"
+        ));
+        let tile = json!({ "tilesetUid": 1, "x": 16, "y": 32, "w": 8, "h": 8 });
+        assert!(matches!(
+            value("Tile", tile),
+            Some(FieldValue::Tile(t)) if (t.tileset_uid, t.x, t.y) == (1, 16, 32)
+        ));
+        assert!(matches!(
+            value("FilePath", json!("a.png")),
+            Some(FieldValue::FilePath(s)) if s == "a.png"
+        ));
+        assert!(matches!(
+            value("LocalEnum.Item", json!("Sword")),
+            Some(FieldValue::LocalEnum { name, value }) if name == "Item" && value == "Sword"
+        ));
+        assert!(matches!(
+            value("ExternEnum.Music", json!("Boss")),
+            Some(FieldValue::ExternEnum { name, value }) if name == "Music" && value == "Boss"
+        ));
+        let Some(FieldValue::Array(items)) = value("Array<Int>", json!([1, null, 3])) else {
+            panic!("not an array");
+        };
+        assert!(matches!(
+            items[..],
+            [FieldValue::Int(1), FieldValue::Null, FieldValue::Int(3)]
+        ));
+        assert!(value("Array<Int>", json!(null)).unwrap().is_null());
+        assert!(value("Point", json!(null)).unwrap().is_null());
+
+        // values that don't match their type
+        assert!(value("Int", json!("3")).is_none());
+        assert!(value("Int", json!(1.5)).is_none());
+        assert!(value("Color", json!("yellow")).is_none());
+        assert!(value("Point", json!({ "cx": 4 })).is_none());
+        assert!(value("Array<Int>", json!([1, "2"])).is_none());
+        assert!(value("Array<Int>", json!(1)).is_none());
+    }
+
+    #[test]
+    fn typed_values_of_the_examples() {
+        let project = crate::Project::new("assets/SeparateLevelFiles.ldtk");
+        let level = &project.levels[0];
+        let types: Vec<FieldType> = level
+            .field_instances
+            .iter()
+            .map(|f| f.field_type().unwrap())
+            .collect();
+        assert_eq!(types, [FieldType::String, FieldType::Color]);
+        let tutorial = level.field_instances[0].typed_value().unwrap();
+        assert!(matches!(tutorial, FieldValue::String(s) if s.starts_with("In this example")));
+        // Level_B has no tutorial
+        let tutorial = project.levels[1].field_instances[0].typed_value().unwrap();
+        assert!(tutorial.is_null());
+
+        let mut broken = level.field_instances[1].clone();
+        broken.value = Some(json!(12));
+        assert!(matches!(
+            broken.typed_value(),
+            Err(FieldError::InvalidValue { field, field_type: FieldType::Color, .. })
+                if field == "textColor"
+        ));
+        broken.field_instance_type = "Colour".to_string();
+        assert!(matches!(
+            broken.typed_value(),
+            Err(FieldError::UnknownType { field_type, .. }) if field_type == "Colour"
+        ));
+    }
+
+    fn field(identifier: &str, field_type: &str, value: Value) -> FieldInstance {
+        FieldInstance {
+            identifier: identifier.to_string(),
+            tile: None,
+            field_instance_type: field_type.to_string(),
+            value: Some(value),
+            def_uid: 0,
+            real_editor_values: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn getters() {
+        let project = crate::Project::new("assets/SeparateLevelFiles.ldtk");
+        let level = &project.levels[0];
+        assert!(
+            level
+                .get_string("tutorial")
+                .unwrap()
+                .ends_with("perspective.")
+        );
+        assert_eq!(
+            level.get_color("textColor").unwrap(),
+            Color::rgb(0xff, 0xdc, 0x00)
+        );
+        assert_eq!(level.field("textColor").unwrap().def_uid, 9);
+
+        let game = crate::Project::from_slice(include_bytes!("../assets/game_1-1-3.ldtk"));
+        let layer = &game.levels[0].layer_instances.as_ref().unwrap()[0];
+        let bird = layer
+            .entity_instances
+            .iter()
+            .find(|e| e.identifier == "Bird")
+            .unwrap();
+        assert_eq!(
+            bird.get_color("color").unwrap(),
+            Color::rgb(0xea, 0x0c, 0xcc)
+        );
+
+        let fields = [
+            field("hp", "Int", json!(3)),
+            field("speed", "Float", json!(1.5)),
+            field("text", "Multilines", json!("a\nb")),
+            field("target", "Point", json!({ "cx": 1, "cy": 2 })),
+            field("item", "LocalEnum.Item", json!("Sword")),
+            field(
+                "path",
+                "Array<Point>",
+                json!([{ "cx": 1, "cy": 2 }, { "cx": 3, "cy": 4 }]),
+            ),
+            field("loot", "Array<ExternEnum.Item>", json!(["Gem", "Key"])),
+        ];
+        let fields = &fields[..];
+        assert_eq!(fields.get_int("hp").unwrap(), 3);
+        assert_eq!(fields.get_float("speed").unwrap(), 1.5);
+        assert_eq!(fields.get_string("text").unwrap(), "a\nb");
+        assert_eq!(fields.get_point("target").unwrap().cx, 1);
+        assert_eq!(fields.get_enum("item").unwrap(), "Sword");
+        let path = fields.get_point_array("path").unwrap();
+        assert_eq!(
+            path.iter().map(|p| (p.cx, p.cy)).collect::<Vec<_>>(),
+            [(1, 2), (3, 4)]
+        );
+        assert_eq!(fields.get_enum_array("loot").unwrap(), ["Gem", "Key"]);
+    }
+
+    #[test]
+    fn getter_errors() {
+        let project = crate::Project::new("assets/SeparateLevelFiles.ldtk");
+        // Level_B has no tutorial
+        let level = &project.levels[1];
+        assert!(matches!(
+            level.get_string("tutorial"),
+            Err(FieldError::Null { field, index: None }) if field == "tutorial"
+        ));
+        assert!(matches!(
+            level.get_string("music"),
+            Err(FieldError::Missing { field }) if field == "music"
+        ));
+        assert!(matches!(
+            level.get_int("textColor"),
+            Err(FieldError::WrongType {
+                expected: "Int",
+                found: FieldType::Color,
+                ..
+            })
+        ));
+        assert!(matches!(
+            level.get_color_array("textColor"),
+            Err(FieldError::WrongType {
+                expected: "Array<Color>",
+                ..
+            })
+        ));
+
+        let fields = [
+            field("hp", "Array<Int>", json!([1, null, 3])),
+            field("item", "LocalEnum.Item", json!(4)),
+        ];
+        let fields = &fields[..];
+        assert!(matches!(
+            fields.get_int_array("hp"),
+            Err(FieldError::Null { index: Some(1), .. })
+        ));
+        assert!(matches!(
+            fields.get_int("hp"),
+            Err(FieldError::WrongType {
+                expected: "Int",
+                ..
+            })
+        ));
+        assert!(matches!(
+            fields.get_enum("item"),
+            Err(FieldError::InvalidValue { .. })
+        ));
+    }
+}
@@ -0,0 +1,479 @@
+// Which levels lead to which. LDtk lists the levels touching (or, with
+// world depths, overlapping) each level in its `__neighbours`; this types
+// their direction and turns the lists into a graph that can be searched.
+
+use crate::{Level, NeighbourLevel, Project, Rect, WorldRef};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+/// Where a neighbour level is, parsed from `NeighbourLevel::dir`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NeighbourDir {
+    North,
+    South,
+    East,
+    West,
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
+    /// `<`: the neighbour overlaps the level at a lower world depth.
+    Lower,
+    /// `>`: the neighbour overlaps the level at a greater world depth.
+    Greater,
+    /// `o`: the levels overlap at the same world depth.
+    Overlap,
+}
+
+impl NeighbourDir {
+    /// Parse a `dir` string. Returns None for directions LDtk doesn't
+    /// write.
+    pub fn parse(s: &str) -> Option<NeighbourDir> {
+        Some(match s {
+            "n" => NeighbourDir::North,
+            "s" => NeighbourDir::South,
+            "e" => NeighbourDir::East,
+            "w" => NeighbourDir::West,
+            "ne" => NeighbourDir::NorthEast,
+            "nw" => NeighbourDir::NorthWest,
+            "se" => NeighbourDir::SouthEast,
+            "sw" => NeighbourDir::SouthWest,
+            "<" => NeighbourDir::Lower,
+            ">" => NeighbourDir::Greater,
+            "o" => NeighbourDir::Overlap,
+            _ => return None,
+        })
+    }
+
+    /// The string LDtk writes for the direction.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NeighbourDir::North => "n",
+            NeighbourDir::South => "s",
+            NeighbourDir::East => "e",
+            NeighbourDir::West => "w",
+            NeighbourDir::NorthEast => "ne",
+            NeighbourDir::NorthWest => "nw",
+            NeighbourDir::SouthEast => "se",
+            NeighbourDir::SouthWest => "sw",
+            NeighbourDir::Lower => "<",
+            NeighbourDir::Greater => ">",
+            NeighbourDir::Overlap => "o",
+        }
+    }
+
+    /// The direction the other way around, as seen from the neighbour.
+    pub fn opposite(self) -> NeighbourDir {
+        match self {
+            NeighbourDir::North => NeighbourDir::South,
+            NeighbourDir::South => NeighbourDir::North,
+            NeighbourDir::East => NeighbourDir::West,
+            NeighbourDir::West => NeighbourDir::East,
+            NeighbourDir::NorthEast => NeighbourDir::SouthWest,
+            NeighbourDir::NorthWest => NeighbourDir::SouthEast,
+            NeighbourDir::SouthEast => NeighbourDir::NorthWest,
+            NeighbourDir::SouthWest => NeighbourDir::NorthEast,
+            NeighbourDir::Lower => NeighbourDir::Greater,
+            NeighbourDir::Greater => NeighbourDir::Lower,
+            NeighbourDir::Overlap => NeighbourDir::Overlap,
+        }
+    }
+
+    /// True for the levels that only touch by a corner.
+    pub fn is_diagonal(self) -> bool {
+        matches!(
+            self,
+            NeighbourDir::NorthEast
+                | NeighbourDir::NorthWest
+                | NeighbourDir::SouthEast
+                | NeighbourDir::SouthWest
+        )
+    }
+
+    /// True for the levels that overlap instead of touching.
+    pub fn is_depth(self) -> bool {
+        matches!(
+            self,
+            NeighbourDir::Lower | NeighbourDir::Greater | NeighbourDir::Overlap
+        )
+    }
+}
+
+impl fmt::Display for NeighbourDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl NeighbourLevel {
+    /// The `dir`, parsed.
+    pub fn direction(&self) -> Option<NeighbourDir> {
+        NeighbourDir::parse(&self.dir)
+    }
+}
+
+/// Part of the border two levels share, in world pixels. It goes from
+/// `(x0, y0)` to `(x1, y1)` and is either horizontal or vertical, or a
+/// single point for levels that only touch by a corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BorderSegment {
+    pub x0: i64,
+    pub y0: i64,
+    pub x1: i64,
+    pub y1: i64,
+}
+
+impl BorderSegment {
+    /// The length of the border in pixels, 0 for a corner.
+    pub fn length(&self) -> i64 {
+        (self.x1 - self.x0) + (self.y1 - self.y0)
+    }
+
+    /// True when the levels only touch by a corner.
+    pub fn is_corner(&self) -> bool {
+        self.length() == 0
+    }
+}
+
+/// A level leading to one of its neighbours.
+#[derive(Debug, Clone, Copy)]
+pub struct LevelEdge<'a> {
+    pub from: &'a Level,
+    pub to: &'a Level,
+    /// Where `to` is, seen from `from`.
+    pub dir: NeighbourDir,
+}
+
+impl LevelEdge<'_> {
+    /// The border the two levels share. None for levels that overlap
+    /// (the depth directions) and for levels without a world position
+    /// (linear layouts).
+    pub fn border(&self) -> Option<BorderSegment> {
+        let a = level_rect(self.from);
+        let b = level_rect(self.to);
+        let x0 = a.x.max(b.x);
+        let x1 = a.right().min(b.right());
+        let y0 = a.y.max(b.y);
+        let y1 = a.bottom().min(b.bottom());
+        // touching levels meet along a line (or in a point), overlapping
+        // ones over an area
+        let touching = (x0 == x1 && y0 <= y1) || (y0 == y1 && x0 <= x1);
+        touching.then_some(BorderSegment { x0, y0, x1, y1 })
+    }
+}
+
+/// The levels of a project linked to their neighbours.
+///
+/// ```ignore
+/// let graph = project.level_graph();
+/// let route = graph.shortest_path(&start.iid, &boss_room.iid)?;
+/// for (level, steps) in graph.bfs(&current.iid) {
+///     if steps <= 2 {
+///         map.reveal(level);
+///     }
+/// }
+/// for edge in graph.neighbours(&current.iid) {
+///     if let Some(border) = edge.border() {
+///         place_transition(edge.to, border);
+///     }
+/// }
+/// ```
+///
+/// The graph is undirected: if LDtk lists `b` as a neighbour of `a` but
+/// not the other way around, the edge back is added anyway. Neighbours in
+/// a direction this crate doesn't know about are left out. Level stubs
+/// have their neighbours too, so external levels don't have to be loaded.
+#[derive(Debug, Clone)]
+pub struct LevelGraph<'a> {
+    levels: Vec<&'a Level>,
+    by_iid: HashMap<&'a str, usize>,
+    edges: Vec<Vec<LevelEdge<'a>>>,
+    // the index of each edge's `to` level, alongside `edges`
+    targets: Vec<Vec<usize>>,
+}
+
+impl<'a> LevelGraph<'a> {
+    /// The levels of every world. Neighbours never cross worlds, so each
+    /// world ends up as one or more separate components.
+    pub fn new(project: &'a Project) -> Self {
+        LevelGraph::from_levels(project.iter_levels())
+    }
+
+    /// The levels of a single world.
+    pub fn from_world(world: WorldRef<'a>) -> Self {
+        LevelGraph::from_levels(world.levels)
+    }
+
+    fn from_levels<I: IntoIterator<Item = &'a Level>>(levels: I) -> Self {
+        let levels: Vec<&Level> = levels.into_iter().collect();
+        let by_iid: HashMap<&str, usize> = levels
+            .iter()
+            .enumerate()
+            .map(|(i, l)| (l.iid.as_str(), i))
+            .collect();
+        let mut graph = LevelGraph {
+            edges: vec![Vec::new(); levels.len()],
+            targets: vec![Vec::new(); levels.len()],
+            levels,
+            by_iid,
+        };
+        for i in 0..graph.levels.len() {
+            let level = graph.levels[i];
+            for n in &level.neighbours {
+                if let Some(dir) = n.direction()
+                    && let Some(&j) = graph.by_iid.get(n.level_iid.as_str())
+                {
+                    graph.link(i, j, dir);
+                    graph.link(j, i, dir.opposite());
+                }
+            }
+        }
+        graph
+    }
+
+    fn link(&mut self, from: usize, to: usize, dir: NeighbourDir) {
+        if from == to || self.targets[from].contains(&to) {
+            return;
+        }
+        self.edges[from].push(LevelEdge {
+            from: self.levels[from],
+            to: self.levels[to],
+            dir,
+        });
+        self.targets[from].push(to);
+    }
+
+    pub fn levels(&self) -> &[&'a Level] {
+        &self.levels
+    }
+
+    pub fn get_level_by_iid(&self, iid: &str) -> Option<&'a Level> {
+        self.by_iid.get(iid).map(|&i| self.levels[i])
+    }
+
+    /// The edges leading out of a level (found by iid), empty if the level
+    /// isn't in the graph.
+    pub fn neighbours(&self, iid: &str) -> &[LevelEdge<'a>] {
+        self.by_iid.get(iid).map_or(&[], |&i| &self.edges[i])
+    }
+
+    /// The edge from one level to another, if they are neighbours.
+    pub fn edge(&self, from_iid: &str, to_iid: &str) -> Option<&LevelEdge<'a>> {
+        self.neighbours(from_iid)
+            .iter()
+            .find(|e| e.to.iid == to_iid)
+    }
+
+    /// Every edge, each pair of neighbours showing up once in each
+    /// direction.
+    pub fn edges(&self) -> impl Iterator<Item = &LevelEdge<'a>> {
+        self.edges.iter().flatten()
+    }
+
+    /// The levels that can be reached from a level (found by iid), in
+    /// breadth first order, with the number of transitions it takes to get
+    /// there. The level itself comes first, at 0.
+    pub fn bfs(&self, from_iid: &str) -> Vec<(&'a Level, usize)> {
+        let Some(&start) = self.by_iid.get(from_iid) else {
+            return Vec::new();
+        };
+        let parents = self.search(start, None);
+        let mut order: Vec<(usize, usize)> = Vec::new();
+        // search() visits in breadth first order, replay it with depths
+        let mut depth = vec![0; self.levels.len()];
+        for &(i, parent) in &parents {
+            if let Some(p) = parent {
+                depth[i] = depth[p] + 1;
+            }
+            order.push((i, depth[i]));
+        }
+        order
+            .into_iter()
+            .map(|(i, d)| (self.levels[i], d))
+            .collect()
+    }
+
+    /// The levels on a shortest route (in number of transitions) from one
+    /// level to another, both included. None if there is no route or
+    /// either level isn't in the graph.
+    pub fn shortest_path(&self, from_iid: &str, to_iid: &str) -> Option<Vec<&'a Level>> {
+        let start = *self.by_iid.get(from_iid)?;
+        let goal = *self.by_iid.get(to_iid)?;
+        let parents: HashMap<usize, Option<usize>> =
+            self.search(start, Some(goal)).into_iter().collect();
+        parents.get(&goal)?;
+        let mut path = vec![self.levels[goal]];
+        let mut at = goal;
+        while let Some(&Some(parent)) = parents.get(&at) {
+            path.push(self.levels[parent]);
+            at = parent;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// The groups of levels linked to each other, in the order of their
+    /// first level.
+    pub fn connected_components(&self) -> Vec<Vec<&'a Level>> {
+        let mut seen = vec![false; self.levels.len()];
+        let mut components = Vec::new();
+        for start in 0..self.levels.len() {
+            if seen[start] {
+                continue;
+            }
+            let mut component: Vec<usize> = self
+                .search(start, None)
+                .into_iter()
+                .map(|(i, _)| i)
+                .collect();
+            component.sort_unstable();
+            for &i in &component {
+                seen[i] = true;
+            }
+            components.push(component.into_iter().map(|i| self.levels[i]).collect());
+        }
+        components
+    }
+
+    // Breadth first search from `start`, stopping early once `goal` is
+    // reached. Returns the visited levels in order, with the level each
+    // was reached from.
+    fn search(&self, start: usize, goal: Option<usize>) -> Vec<(usize, Option<usize>)> {
+        let mut visited = vec![false; self.levels.len()];
+        let mut order = vec![(start, None)];
+        let mut queue = VecDeque::from([start]);
+        visited[start] = true;
+        while let Some(i) = queue.pop_front() {
+            if Some(i) == goal {
+                break;
+            }
+            for &j in &self.targets[i] {
+                if !visited[j] {
+                    visited[j] = true;
+                    order.push((j, Some(i)));
+                    queue.push_back(j);
+                }
+            }
+        }
+        order
+    }
+}
+
+impl Project {
+    /// Build a [`LevelGraph`] of every level of the project.
+    pub fn level_graph(&self) -> LevelGraph<'_> {
+        LevelGraph::new(self)
+    }
+}
+
+impl<'a> WorldRef<'a> {
+    /// Build a [`LevelGraph`] of the levels of this world.
+    pub fn level_graph(self) -> LevelGraph<'a> {
+        LevelGraph::from_world(self)
+    }
+}
+
+fn level_rect(level: &Level) -> Rect {
+    Rect::new(level.world_x, level.world_y, level.px_wid, level.px_hei)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    // The levels of SeparateLevelFiles.ldtk laid out in a GridVania world,
+    // plus a copy of Level_C far away from the others:
+    //
+    //   A B
+    //       C
+    //
+    //   D
+    fn project() -> Project {
+        let mut json: Value =
+            serde_json::from_slice(include_bytes!("../assets/SeparateLevelFiles.ldtk")).unwrap();
+        json["worldLayout"] = "GridVania".into();
+        let levels = json["levels"].as_array_mut().unwrap();
+        let mut d = levels[2].clone();
+        d["iid"] = "D".into();
+        d["identifier"] = "Level_D".into();
+        levels.push(d);
+        let place = [(0, 0), (240, 0), (480, 240), (0, 960)];
+        let neighbours = [
+            json!([{ "levelIid": "B", "dir": "e" }]),
+            json!([{ "levelIid": "A", "dir": "w" }, { "levelIid": "C", "dir": "se" }]),
+            json!([{ "levelIid": "B", "dir": "nw" }]),
+            json!([]),
+        ];
+        for (i, level) in levels.iter_mut().enumerate() {
+            level["iid"] = ["A", "B", "C", "D"][i].into();
+            level["worldX"] = place[i].0.into();
+            level["worldY"] = place[i].1.into();
+            level["__neighbours"] = neighbours[i].clone();
+        }
+        Project::try_from_slice(&serde_json::to_vec(&json).unwrap()).unwrap()
+    }
+
+    fn iids(levels: &[&Level]) -> Vec<String> {
+        levels.iter().map(|l| l.iid.clone()).collect()
+    }
+
+    #[test]
+    fn shortest_path() {
+        let project = project();
+        let graph = project.level_graph();
+        let path = |from, to| graph.shortest_path(from, to).map(|p| iids(&p));
+        assert_eq!(path("A", "C").unwrap(), ["A", "B", "C"]);
+        assert_eq!(path("C", "A").unwrap(), ["C", "B", "A"]);
+        assert_eq!(path("B", "B").unwrap(), ["B"]);
+        assert_eq!(path("A", "D"), None);
+        assert_eq!(path("A", "nowhere"), None);
+    }
+
+    #[test]
+    fn connected_components() {
+        let project = project();
+        let components: Vec<Vec<String>> = project
+            .level_graph()
+            .connected_components()
+            .iter()
+            .map(|c| iids(c))
+            .collect();
+        assert_eq!(components, [vec!["A", "B", "C"], vec!["D"]]);
+    }
+
+    #[test]
+    fn borders() {
+        let project = project();
+        let graph = project.level_graph();
+        // Level_A is 16 pixels shorter than Level_B
+        let side = graph.edge("A", "B").unwrap();
+        assert_eq!(side.dir, NeighbourDir::East);
+        let border = side.border().unwrap();
+        assert_eq!(
+            border,
+            BorderSegment {
+                x0: 240,
+                y0: 0,
+                x1: 240,
+                y1: 224
+            }
+        );
+        assert_eq!(border.length(), 224);
+        assert!(!border.is_corner());
+
+        let corner = graph.edge("C", "B").unwrap();
+        assert_eq!(corner.dir, NeighbourDir::NorthWest);
+        let border = corner.border().unwrap();
+        assert_eq!(
+            border,
+            BorderSegment {
+                x0: 480,
+                y0: 240,
+                x1: 480,
+                y1: 240
+            }
+        );
+        assert!(border.is_corner());
+    }
+}
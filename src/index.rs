@@ -0,0 +1,301 @@
+// Hash maps over a loaded project, for when the linear lookups of Project
+// (get_level, get_world_by_iid, ...) are called often enough to matter.
+// Everything is borrowed from the project, so the index has to be rebuilt
+// when the project changes (after loading external levels, say).
+
+use crate::{
+    EntityDefinition, EntityInstance, EntityRef, EnumDefinition, FieldDefinition, LayerDefinition,
+    LayerInstance, Level, Project, TilesetDefinition, WorldRef,
+};
+use std::collections::HashMap;
+
+/// A layer instance with the level and world it's in.
+#[derive(Debug, Clone, Copy)]
+pub struct LayerLocation<'a> {
+    pub layer: &'a LayerInstance,
+    pub level: &'a Level,
+    pub world: WorldRef<'a>,
+}
+
+/// An entity instance with the layer, level and world it's in.
+#[derive(Debug, Clone, Copy)]
+pub struct EntityLocation<'a> {
+    pub entity: &'a EntityInstance,
+    pub layer: &'a LayerInstance,
+    pub level: &'a Level,
+    pub world: WorldRef<'a>,
+}
+
+/// Constant time lookups of the levels, worlds, layers, entities and
+/// definitions of a project by uid or iid.
+///
+/// ```ignore
+/// let index = project.index();
+/// let door = index.get_entity_by_iid(&target.entity_iid)?;
+/// let location = index.entity_location(&target.entity_iid)?;
+/// println!("{} is in {}", door.identifier, location.level.identifier);
+/// ```
+///
+/// Only what is loaded gets indexed: the layers and entities of external
+/// levels that haven't been loaded yet are missing.
+#[derive(Debug, Clone)]
+pub struct ProjectIndex<'a> {
+    project: &'a Project,
+    worlds: HashMap<&'a str, WorldRef<'a>>,
+    levels: HashMap<i64, &'a Level>,
+    levels_by_iid: HashMap<&'a str, (&'a Level, WorldRef<'a>)>,
+    layers: HashMap<&'a str, LayerLocation<'a>>,
+    entities: HashMap<&'a str, EntityLocation<'a>>,
+    entity_defs: HashMap<i64, &'a EntityDefinition>,
+    layer_defs: HashMap<i64, &'a LayerDefinition>,
+    tileset_defs: HashMap<i64, &'a TilesetDefinition>,
+    enum_defs: HashMap<i64, &'a EnumDefinition>,
+    field_defs: HashMap<i64, &'a FieldDefinition>,
+}
+
+impl<'a> ProjectIndex<'a> {
+    pub fn new(project: &'a Project) -> Self {
+        let mut index = ProjectIndex {
+            project,
+            worlds: HashMap::new(),
+            levels: HashMap::new(),
+            levels_by_iid: HashMap::new(),
+            layers: HashMap::new(),
+            entities: HashMap::new(),
+            entity_defs: HashMap::new(),
+            layer_defs: HashMap::new(),
+            tileset_defs: HashMap::new(),
+            enum_defs: HashMap::new(),
+            field_defs: HashMap::new(),
+        };
+
+        for world in project.iter_worlds() {
+            index.worlds.insert(world.iid, world);
+            for level in world.levels {
+                index.levels.insert(level.uid, level);
+                index.levels_by_iid.insert(&level.iid, (level, world));
+                for layer in level.layer_instances.iter().flatten() {
+                    index.layers.insert(
+                        &layer.iid,
+                        LayerLocation {
+                            layer,
+                            level,
+                            world,
+                        },
+                    );
+                    for entity in &layer.entity_instances {
+                        index.entities.insert(
+                            &entity.iid,
+                            EntityLocation {
+                                entity,
+                                layer,
+                                level,
+                                world,
+                            },
+                        );
+                    }
+                }
+            }
+        }
+
+        let defs = &project.defs;
+        for def in &defs.entities {
+            index.entity_defs.insert(def.uid, def);
+            for field in &def.field_defs {
+                index.field_defs.insert(field.uid, field);
+            }
+        }
+        for field in &defs.level_fields {
+            index.field_defs.insert(field.uid, field);
+        }
+        for def in &defs.layers {
+            index.layer_defs.insert(def.uid, def);
+        }
+        for def in &defs.tilesets {
+            index.tileset_defs.insert(def.uid, def);
+        }
+        for def in defs.enums.iter().chain(&defs.external_enums) {
+            index.enum_defs.insert(def.uid, def);
+        }
+        index
+    }
+
+    /// The project the index was built from.
+    pub fn project(&self) -> &'a Project {
+        self.project
+    }
+
+    pub fn get_world_by_iid(&self, iid: &str) -> Option<WorldRef<'a>> {
+        self.worlds.get(iid).copied()
+    }
+
+    pub fn get_level(&self, uid: i64) -> Option<&'a Level> {
+        self.levels.get(&uid).copied()
+    }
+
+    pub fn get_level_by_iid(&self, iid: &str) -> Option<&'a Level> {
+        self.levels_by_iid.get(iid).map(|(level, _)| *level)
+    }
+
+    pub fn get_layer_by_iid(&self, iid: &str) -> Option<&'a LayerInstance> {
+        self.layers.get(iid).map(|l| l.layer)
+    }
+
+    pub fn get_entity_by_iid(&self, iid: &str) -> Option<&'a EntityInstance> {
+        self.entities.get(iid).map(|e| e.entity)
+    }
+
+    pub fn get_entity_def(&self, uid: i64) -> Option<&'a EntityDefinition> {
+        self.entity_defs.get(&uid).copied()
+    }
+
+    pub fn get_layer_def(&self, uid: i64) -> Option<&'a LayerDefinition> {
+        self.layer_defs.get(&uid).copied()
+    }
+
+    pub fn get_tileset_def(&self, uid: i64) -> Option<&'a TilesetDefinition> {
+        self.tileset_defs.get(&uid).copied()
+    }
+
+    /// Local and external enums alike.
+    pub fn get_enum_def(&self, uid: i64) -> Option<&'a EnumDefinition> {
+        self.enum_defs.get(&uid).copied()
+    }
+
+    /// Entity and level fields alike.
+    pub fn get_field_def(&self, uid: i64) -> Option<&'a FieldDefinition> {
+        self.field_defs.get(&uid).copied()
+    }
+
+    /// The world a level (found by iid) is in.
+    pub fn world_of_level(&self, level_iid: &str) -> Option<WorldRef<'a>> {
+        self.levels_by_iid.get(level_iid).map(|(_, world)| *world)
+    }
+
+    /// The level and world a layer instance (found by iid) is in.
+    pub fn layer_location(&self, iid: &str) -> Option<LayerLocation<'a>> {
+        self.layers.get(iid).copied()
+    }
+
+    /// The layer, level and world an entity (found by iid) is in.
+    pub fn entity_location(&self, iid: &str) -> Option<EntityLocation<'a>> {
+        self.entities.get(iid).copied()
+    }
+
+    /// The entity a reference points to, see [`Project::resolve`].
+    pub fn resolve(&self, r: &EntityRef) -> Option<EntityLocation<'a>> {
+        self.entity_location(&r.entity_iid)
+    }
+}
+
+impl Project {
+    /// Build a [`ProjectIndex`] of the project as it is now.
+    pub fn index(&self) -> ProjectIndex<'_> {
+        ProjectIndex::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookups() {
+        let project = Project::new("assets/game_1-1-3.ldtk");
+        let index = project.index();
+        let level = &project.levels[0];
+        assert!(std::ptr::eq(index.get_level(level.uid).unwrap(), level));
+        assert!(std::ptr::eq(
+            index.get_level_by_iid(&level.iid).unwrap(),
+            level
+        ));
+        assert!(index.get_level(-1).is_none());
+        assert!(index.get_level_by_iid("nope").is_none());
+
+        let world = index.world_of_level(&level.iid).unwrap();
+        assert_eq!(world.iid, project.iter_worlds().next().unwrap().iid);
+        assert_eq!(index.get_world_by_iid(world.iid).unwrap().iid, world.iid);
+
+        let layers = level.layer_instances.as_ref().unwrap();
+        let mut entities = 0;
+        for layer in layers {
+            let location = index.layer_location(&layer.iid).unwrap();
+            assert!(std::ptr::eq(location.layer, layer));
+            assert!(std::ptr::eq(location.level, level));
+            assert!(std::ptr::eq(
+                index.get_layer_def(layer.layer_def_uid).unwrap(),
+                project.get_layer_def(layer.layer_def_uid).unwrap()
+            ));
+            for entity in &layer.entity_instances {
+                let location = index.entity_location(&entity.iid).unwrap();
+                assert!(std::ptr::eq(location.entity, entity));
+                assert!(std::ptr::eq(location.layer, layer));
+                assert_eq!(location.world.iid, world.iid);
+                let def = index.get_entity_def(entity.def_uid).unwrap();
+                assert_eq!(def.identifier, entity.identifier);
+                for field in &entity.field_instances {
+                    let field_def = index.get_field_def(field.def_uid).unwrap();
+                    assert_eq!(field_def.identifier, field.identifier);
+                }
+                entities += 1;
+            }
+        }
+        assert!(entities > 0);
+        assert!(index.get_entity_by_iid("nope").is_none());
+    }
+
+    #[test]
+    fn resolve() {
+        let project = Project::new("assets/game_1-1-3.ldtk");
+        let index = project.index();
+        let layer = &project.levels[0].layer_instances.as_ref().unwrap()[0];
+        let hero = layer
+            .entity_instances
+            .iter()
+            .find(|e| e.identifier == "Hero")
+            .unwrap();
+        let world = index.world_of_level(&project.levels[0].iid).unwrap();
+        let r = EntityRef {
+            entity_iid: hero.iid.clone(),
+            layer_iid: layer.iid.clone(),
+            level_iid: project.levels[0].iid.clone(),
+            world_iid: world.iid.to_string(),
+        };
+        let location = index.resolve(&r).unwrap();
+        assert!(std::ptr::eq(location.entity, hero));
+        assert_eq!(
+            project.resolve(&r).map(|l| l.entity.px.clone()),
+            Some(vec![80, 8])
+        );
+
+        let dangling = EntityRef {
+            entity_iid: "nope".to_string(),
+            ..r
+        };
+        assert!(index.resolve(&dangling).is_none());
+    }
+
+    #[test]
+    fn definitions() {
+        let project = Project::new("assets/game_1-1-3.ldtk");
+        let index = project.index();
+        let defs = &project.defs;
+        for tileset in &defs.tilesets {
+            assert_eq!(
+                index.get_tileset_def(tileset.uid).unwrap().identifier,
+                tileset.identifier
+            );
+        }
+        for def in defs.enums.iter().chain(&defs.external_enums) {
+            assert_eq!(
+                index.get_enum_def(def.uid).unwrap().identifier,
+                def.identifier
+            );
+        }
+        for field in &defs.level_fields {
+            assert!(std::ptr::eq(index.get_field_def(field.uid).unwrap(), field));
+        }
+        assert!(index.get_entity_def(-1).is_none());
+        assert!(index.get_tileset_def(-1).is_none());
+    }
+}
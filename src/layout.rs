@@ -0,0 +1,257 @@
+// Making sense of a world's layout. Levels of Free and GridVania worlds
+// have a position in world pixels, GridVania snapping it to a grid of
+// world_grid_width by world_grid_height cells. Levels of linear worlds
+// have no position at all (LDtk writes -1), only an order.
+
+use crate::{Level, Rect, WorldLayout, WorldRef};
+
+impl<'a> WorldRef<'a> {
+    pub fn is_grid_vania(&self) -> bool {
+        matches!(self.world_layout, Some(WorldLayout::GridVania))
+    }
+
+    /// True for `LinearHorizontal` and `LinearVertical` worlds.
+    pub fn is_linear(&self) -> bool {
+        matches!(
+            self.world_layout,
+            Some(WorldLayout::LinearHorizontal | WorldLayout::LinearVertical)
+        )
+    }
+
+    /// The position of a level in the order of a linear world. None in
+    /// other layouts and for levels of other worlds.
+    pub fn linear_index(&self, level: &Level) -> Option<usize> {
+        if !self.is_linear() {
+            return None;
+        }
+        self.levels.iter().position(|l| l.iid == level.iid)
+    }
+
+    /// The grid cell of a level's top left corner in a GridVania world.
+    pub fn grid_cell(&self, level: &Level) -> Option<(i64, i64)> {
+        self.grid_rect(level).map(|r| (r.x, r.y))
+    }
+
+    /// The grid cells a level covers in a GridVania world, in cells rather
+    /// than pixels.
+    pub fn grid_rect(&self, level: &Level) -> Option<Rect> {
+        let (gw, gh) = (self.world_grid_width, self.world_grid_height);
+        if !self.is_grid_vania() || gw <= 0 || gh <= 0 {
+            return None;
+        }
+        let x0 = level.world_x.div_euclid(gw);
+        let y0 = level.world_y.div_euclid(gh);
+        let x1 = (level.world_x + level.px_wid.max(1) - 1).div_euclid(gw);
+        let y1 = (level.world_y + level.px_hei.max(1) - 1).div_euclid(gh);
+        Some(Rect::new(x0, y0, x1 - x0 + 1, y1 - y0 + 1))
+    }
+
+    /// The levels covering a grid cell of a GridVania world, from the
+    /// lowest world depth to the highest.
+    pub fn levels_at_grid_cell(&self, x: i64, y: i64) -> Vec<&'a Level> {
+        let mut levels: Vec<&Level> = self
+            .levels
+            .iter()
+            .filter(|l| {
+                self.grid_rect(l)
+                    .is_some_and(|r| r.contains(x as f64, y as f64))
+            })
+            .collect();
+        levels.sort_by_key(|l| l.world_depth);
+        levels
+    }
+
+    /// The level covering a grid cell of a GridVania world at a world
+    /// depth.
+    pub fn level_at_grid_cell(&self, x: i64, y: i64, depth: i64) -> Option<&'a Level> {
+        self.levels_at_grid_cell(x, y)
+            .into_iter()
+            .find(|l| l.world_depth == depth)
+    }
+
+    /// Every level with its bounds in world pixels. Levels of linear worlds
+    /// are laid out edge to edge in their order, starting at 0, 0.
+    pub fn iter_level_bounds(&self) -> impl Iterator<Item = (&'a Level, Rect)> {
+        let layout = self.world_layout;
+        let mut offset = 0;
+        self.levels.iter().map(move |l| {
+            let (x, y) = match layout {
+                Some(WorldLayout::LinearHorizontal) => (offset, 0),
+                Some(WorldLayout::LinearVertical) => (0, offset),
+                _ => (l.world_x, l.world_y),
+            };
+            offset += match layout {
+                Some(WorldLayout::LinearVertical) => l.px_hei,
+                _ => l.px_wid,
+            };
+            (l, Rect::new(x, y, l.px_wid, l.px_hei))
+        })
+    }
+
+    /// The world pixels of a level's top left corner: its `world_x` and
+    /// `world_y`, except in linear worlds where it comes from
+    /// [`WorldRef::iter_level_bounds`]. None for levels of other worlds
+    /// in linear layouts.
+    pub fn level_origin(&self, level: &Level) -> Option<(i64, i64)> {
+        if !self.is_linear() {
+            return Some((level.world_x, level.world_y));
+        }
+        self.level_bounds(level).map(|r| (r.x, r.y))
+    }
+
+    /// The bounds of a level in world pixels, see
+    /// [`WorldRef::iter_level_bounds`]. None for levels of other worlds.
+    pub fn level_bounds(&self, level: &Level) -> Option<Rect> {
+        self.iter_level_bounds()
+            .find(|(l, _)| l.iid == level.iid)
+            .map(|(_, r)| r)
+    }
+
+    /// The area covered by all the levels of the world, in world pixels.
+    /// None for worlds without levels.
+    pub fn bounds(&self) -> Option<Rect> {
+        union(self.iter_level_bounds().map(|(_, r)| r))
+    }
+
+    /// Same as [`WorldRef::bounds`], for the levels at one world depth.
+    pub fn bounds_at_depth(&self, depth: i64) -> Option<Rect> {
+        union(
+            self.iter_level_bounds()
+                .filter(|(l, _)| l.world_depth == depth)
+                .map(|(_, r)| r),
+        )
+    }
+
+    /// The world depths the levels are at, lowest first.
+    pub fn depths(&self) -> Vec<i64> {
+        let mut depths: Vec<i64> = self.levels.iter().map(|l| l.world_depth).collect();
+        depths.sort_unstable();
+        depths.dedup();
+        depths
+    }
+
+    /// The levels at a world depth, in order.
+    pub fn levels_at_depth(&self, depth: i64) -> impl Iterator<Item = &'a Level> {
+        self.levels.iter().filter(move |l| l.world_depth == depth)
+    }
+}
+
+// The smallest rectangle around all of them.
+fn union<I: Iterator<Item = Rect>>(mut rects: I) -> Option<Rect> {
+    let first = rects.next()?;
+    let (mut x0, mut y0) = (first.x, first.y);
+    let (mut x1, mut y1) = (first.right(), first.bottom());
+    for r in rects {
+        x0 = x0.min(r.x);
+        y0 = y0.min(r.y);
+        x1 = x1.max(r.right());
+        y1 = y1.max(r.bottom());
+    }
+    Some(Rect::new(x0, y0, x1 - x0, y1 - y0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Project;
+    use serde_json::Value;
+
+    // SeparateLevelFiles.ldtk with another layout. Its levels are 240
+    // pixels wide, Level_A is 224 pixels high and the others 240.
+    fn project(layout: &str, edit: impl FnOnce(&mut [Value])) -> Project {
+        let mut json: Value =
+            serde_json::from_slice(include_bytes!("../assets/SeparateLevelFiles.ldtk")).unwrap();
+        json["worldLayout"] = layout.into();
+        edit(json["levels"].as_array_mut().unwrap());
+        Project::try_from_slice(&serde_json::to_vec(&json).unwrap()).unwrap()
+    }
+
+    // The world grid is 240 by 240 pixels:
+    //
+    //   A B C C
+    //       C C
+    //
+    // with C (480 by 480 pixels) at depth -1 and the others at 0.
+    fn grid_vania() -> Project {
+        project("GridVania", |levels| {
+            for (level, (x, y)) in levels.iter_mut().zip([(-240, 0), (0, 0), (0, 0)]) {
+                level["worldX"] = x.into();
+                level["worldY"] = y.into();
+            }
+            levels[2]["pxWid"] = 480.into();
+            levels[2]["pxHei"] = 480.into();
+            levels[2]["worldDepth"] = (-1).into();
+        })
+    }
+
+    fn identifiers(levels: &[&Level]) -> Vec<String> {
+        levels.iter().map(|l| l.identifier.clone()).collect()
+    }
+
+    #[test]
+    fn grid_rect() {
+        let project = grid_vania();
+        let world = project.iter_worlds().next().unwrap();
+        assert!(world.is_grid_vania());
+        let [a, b, c] = [0, 1, 2].map(|i| &project.levels[i]);
+        assert_eq!(world.grid_rect(a), Some(Rect::new(-1, 0, 1, 1)));
+        assert_eq!(world.grid_cell(a), Some((-1, 0)));
+        assert_eq!(world.grid_rect(b), Some(Rect::new(0, 0, 1, 1)));
+        assert_eq!(world.grid_rect(c), Some(Rect::new(0, 0, 2, 2)));
+        assert_eq!(world.linear_index(a), None);
+    }
+
+    #[test]
+    fn levels_at_grid_cell_by_depth() {
+        let project = grid_vania();
+        let world = project.iter_worlds().next().unwrap();
+        let at = |x, y| identifiers(&world.levels_at_grid_cell(x, y));
+        assert_eq!(at(0, 0), ["Level_C", "Level_B"]);
+        assert_eq!(at(1, 1), ["Level_C"]);
+        assert_eq!(at(-1, 0), ["Level_A"]);
+        assert!(at(-1, 1).is_empty());
+        let level = |x, y, depth| world.level_at_grid_cell(x, y, depth).map(|l| &l.identifier);
+        assert_eq!(level(0, 0, 0).unwrap(), "Level_B");
+        assert_eq!(level(1, 1, -1).unwrap(), "Level_C");
+        assert_eq!(level(1, 1, 0), None);
+    }
+
+    #[test]
+    fn bounds_by_depth() {
+        let project = grid_vania();
+        let world = project.iter_worlds().next().unwrap();
+        assert_eq!(world.depths(), [-1, 0]);
+        assert_eq!(world.bounds(), Some(Rect::new(-240, 0, 720, 480)));
+        assert_eq!(world.bounds_at_depth(0), Some(Rect::new(-240, 0, 480, 240)));
+        assert_eq!(world.bounds_at_depth(-1), Some(Rect::new(0, 0, 480, 480)));
+        assert_eq!(world.bounds_at_depth(1), None);
+        let at_depth: Vec<&Level> = world.levels_at_depth(0).collect();
+        assert_eq!(identifiers(&at_depth), ["Level_A", "Level_B"]);
+    }
+
+    #[test]
+    fn linear_offsets() {
+        let vertical = project("LinearVertical", |_| {});
+        let world = vertical.iter_worlds().next().unwrap();
+        let bounds: Vec<Rect> = world.iter_level_bounds().map(|(_, r)| r).collect();
+        assert_eq!(
+            bounds,
+            [
+                Rect::new(0, 0, 240, 224),
+                Rect::new(0, 224, 240, 240),
+                Rect::new(0, 464, 240, 240),
+            ]
+        );
+        assert_eq!(world.bounds(), Some(Rect::new(0, 0, 240, 704)));
+        let c = &vertical.levels[2];
+        assert_eq!(world.linear_index(c), Some(2));
+        assert_eq!(world.level_origin(c), Some((0, 464)));
+        assert_eq!(world.grid_rect(c), None);
+
+        let horizontal = project("LinearHorizontal", |_| {});
+        let world = horizontal.iter_worlds().next().unwrap();
+        let origins: Vec<(i64, i64)> = world.iter_level_bounds().map(|(_, r)| (r.x, r.y)).collect();
+        assert_eq!(origins, [(0, 0), (240, 0), (480, 0)]);
+        assert_eq!(world.bounds(), Some(Rect::new(0, 0, 720, 240)));
+    }
+}
@@ -12,99 +12,555 @@
 //! Level::new(f: Path) --- loads a single external level file
 //! ```
 
+//! Every loader also has a `try_` version that returns a `Result` with an
+//! [`LdtkError`] instead of panicking.
+//!
+//! Files saved by older versions of LDtk (back to 0.6.3) are read with the
+//! schema of the version that wrote them and upgraded to the current
+//! structs automatically.
+
+#[cfg(feature = "archive")]
+mod archive;
+mod asset;
+mod async_source;
+mod codegen;
+mod color;
+mod coords;
+mod de;
+mod definitions;
+mod error;
+mod fields;
+mod graph;
+mod index;
+mod layout;
+mod observe;
+mod query;
+mod resolve;
+mod spatial;
+mod store;
+mod toc;
+mod upgrade;
+mod validate;
+mod world;
+
+// Generated from the LDtk JSON schema of each release, see the README.
+// The last one is the current model, the others are used to upgrade old
+// files and are re-exported below.
+#[allow(clippy::doc_lazy_continuation, clippy::enum_variant_names)]
+mod json_0_10_beta1;
+#[allow(clippy::doc_lazy_continuation, clippy::enum_variant_names)]
+mod json_0_10_beta2;
+#[allow(clippy::doc_lazy_continuation, clippy::enum_variant_names)]
+mod json_0_6_3;
+#[allow(clippy::doc_lazy_continuation, clippy::enum_variant_names)]
+mod json_0_6_4;
+#[allow(clippy::doc_lazy_continuation, clippy::enum_variant_names)]
+mod json_0_7_0;
+#[allow(clippy::doc_lazy_continuation, clippy::enum_variant_names)]
+mod json_0_8_1;
+#[allow(clippy::doc_lazy_continuation, clippy::enum_variant_names)]
+mod json_0_9_2;
+#[allow(clippy::doc_lazy_continuation, clippy::enum_variant_names)]
+mod json_0_9_3;
+#[allow(clippy::doc_lazy_continuation, clippy::enum_variant_names)]
+mod json_1_0_0;
+#[allow(clippy::doc_lazy_continuation, clippy::enum_variant_names)]
+mod json_1_1_0;
+#[allow(clippy::doc_lazy_continuation, clippy::enum_variant_names)]
+mod json_1_1_3;
+#[allow(clippy::doc_lazy_continuation)]
 mod json_1_5_3;
 
+#[cfg(feature = "archive")]
+pub use archive::ArchiveSource;
+pub use asset::{AssetSource, EmbeddedSource, FileSource, MemorySource};
+pub use async_source::{AsyncAssetSource, LoadProgress};
+pub use codegen::CodeGenerator;
+pub use color::Color;
+pub use de::{FieldsDeserializer, from_fields};
+pub use error::{FieldError, LdtkError};
+pub use fields::{EntityRef, FieldAccess, FieldType, FieldValue};
+pub use graph::{BorderSegment, LevelEdge, LevelGraph, NeighbourDir};
+pub use index::{EntityLocation, LayerLocation, ProjectIndex};
 pub use json_1_5_3::*;
+pub use observe::LoadObserver;
+pub use query::EntityQuery;
+pub use spatial::{Rect, SpatialIndex};
+pub use store::LevelStore;
+pub use toc::{TableOfContents, TocEntry};
+pub use upgrade::SchemaVersion;
+pub use validate::{FieldLocation, FieldViolation, ViolationKind};
+pub use world::WorldRef;
+
+use observe::debug;
 use std::{
     fs::File,
-    path::{Path, PathBuf}, io::BufReader,
+    io::{BufReader, Read},
+    path::{Path, PathBuf},
 };
 
+// The models generated from older schemas, for code that has to read
+// files exactly the way a given version of LDtk wrote them. Each one is
+// behind a cargo feature of the same name.
+
+#[cfg(feature = "v0_6_3")]
+pub mod v0_6_3 {
+    //! Model generated from the LDtk 0.6.3 JSON schema.
+    pub use crate::json_0_6_3::*;
+}
+
+#[cfg(feature = "v0_6_4")]
+pub mod v0_6_4 {
+    //! Model generated from the LDtk 0.6.4 JSON schema.
+    pub use crate::json_0_6_4::*;
+}
+
+#[cfg(feature = "v0_7_0")]
+pub mod v0_7_0 {
+    //! Model generated from the LDtk 0.7.0 JSON schema.
+    pub use crate::json_0_7_0::*;
+}
+
+#[cfg(feature = "v0_8_1")]
+pub mod v0_8_1 {
+    //! Model generated from the LDtk 0.8.1 JSON schema.
+    pub use crate::json_0_8_1::*;
+}
+
+#[cfg(feature = "v0_9_2")]
+pub mod v0_9_2 {
+    //! Model generated from the LDtk 0.9.2 JSON schema.
+    pub use crate::json_0_9_2::*;
+}
+
+#[cfg(feature = "v0_9_3")]
+pub mod v0_9_3 {
+    //! Model generated from the LDtk 0.9.3 JSON schema.
+    pub use crate::json_0_9_3::*;
+}
+
+#[cfg(feature = "v0_10_beta1")]
+pub mod v0_10_beta1 {
+    //! Model generated from the LDtk 0.10.0-beta1 JSON schema.
+    pub use crate::json_0_10_beta1::*;
+}
+
+#[cfg(feature = "v0_10_beta2")]
+pub mod v0_10_beta2 {
+    //! Model generated from the LDtk 0.10.0-beta2 JSON schema.
+    pub use crate::json_0_10_beta2::*;
+}
+
+#[cfg(feature = "v1_0_0")]
+pub mod v1_0_0 {
+    //! Model generated from the LDtk 1.0.0 JSON schema.
+    pub use crate::json_1_0_0::*;
+}
+
+#[cfg(feature = "v1_1_0")]
+pub mod v1_1_0 {
+    //! Model generated from the LDtk 1.1.0 JSON schema.
+    pub use crate::json_1_1_0::*;
+}
+
+#[cfg(feature = "v1_1_3")]
+pub mod v1_1_3 {
+    //! Model generated from the LDtk 1.1.3 JSON schema.
+    pub use crate::json_1_1_3::*;
+}
+
+// The current model under its own name as well, so code written against
+// it keeps compiling when the crate moves to a newer schema.
+pub mod v1_5_3 {
+    //! Model generated from the LDtk 1.5.3 JSON schema. These are the same
+    //! types as the ones at the root of the crate.
+    pub use crate::json_1_5_3::*;
+}
+
+/// Which of the generated models matches a project (or external level)
+/// file, based on its `jsonVersion` (or `__header__.appVersion`). Only
+/// the version is looked at, the rest of the file isn't parsed. Returns
+/// None if the file doesn't say which version of LDtk wrote it.
+pub fn detect_version(b: &[u8]) -> Option<SchemaVersion> {
+    SchemaVersion::detect(b)
+}
+
 // this struct name has to match the auto-generated top-level struct.
 // Currently mirroring the LDTK Haxe API as best I can figure out.
 impl Project {
     pub fn new<P: AsRef<Path>>(f: P) -> Self {
-        let mut o = Project::load_project(&f);
+        Project::try_new(f).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    // Load the project file and, if the project uses them, all of the
+    // external level files.
+    pub fn try_new<P: AsRef<Path>>(f: P) -> Result<Self, LdtkError> {
+        let mut o = Project::try_load_project(&f)?;
         if o.external_levels {
-            o.load_external_levels(f);
+            o.try_load_external_levels(f)?;
         }
-        o
+        Ok(o)
+    }
+
+    // Same as try_new(), but reads the project file and its external
+    // levels from an AssetSource. `f` is the name of the project file in
+    // the source, usually just something like "game.ldtk".
+    pub fn try_from_source<S: AssetSource + ?Sized>(
+        source: &S,
+        f: &str,
+    ) -> Result<Self, LdtkError> {
+        Project::try_from_source_with_observer(source, f, &())
+    }
+
+    // Same as try_from_source(), reporting progress to a LoadObserver.
+    pub fn try_from_source_with_observer<S, O>(
+        source: &S,
+        f: &str,
+        observer: &O,
+    ) -> Result<Self, LdtkError>
+    where
+        S: AssetSource + ?Sized,
+        O: LoadObserver + ?Sized,
+    {
+        let b = asset::read(source, f)?;
+        observer.bytes_read(f, b.len());
+        let mut o =
+            parse_project(&b).map_err(|e| LdtkError::from_json(e, Some(source.location(f))))?;
+        observer.project_parsed(&o);
+        if o.external_levels {
+            o.try_load_external_levels_with_observer(source, observer)?;
+        }
+        Ok(o)
     }
 
     // Read in an LDTK project file
     pub fn load_project<P: AsRef<Path>>(f: P) -> Self {
-        //let file = File::open(f).expect("project file not found");
-        let file = BufReader::new(File::open(f).expect("project file not found"));
-        let o: Project = serde_json::from_reader(file).expect("error while reading");
-        o
+        Project::try_load_project(f).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_load_project<P: AsRef<Path>>(f: P) -> Result<Self, LdtkError> {
+        let path = f.as_ref();
+        let b = std::fs::read(path).map_err(|e| LdtkError::from_io(e, Some(path.to_path_buf())))?;
+        parse_project(&b).map_err(|e| LdtkError::from_json(e, Some(path.to_path_buf())))
     }
 
     pub fn from_buf(b: BufReader<File>) -> Self {
-        let o: Project = serde_json::from_reader(b).expect("error while reading");
-        o
+        Project::try_from_buf(b).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_from_buf(mut b: BufReader<File>) -> Result<Self, LdtkError> {
+        let mut buf = Vec::new();
+        b.read_to_end(&mut buf)
+            .map_err(|e| LdtkError::from_io(e, None))?;
+        Project::try_from_slice(&buf)
     }
 
     pub fn from_slice(b: &[u8]) -> Self {
-        let o: Project = serde_json::from_slice(b).expect("error while reading");
-        o
+        Project::try_from_slice(b).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_from_slice(b: &[u8]) -> Result<Self, LdtkError> {
+        parse_project(b).map_err(|e| LdtkError::from_json(e, None))
     }
 
-    // Remove any items in the project.levels Vec ... useful when you
-    // get external file info and want to replace the items with more
-    // complete data extrated from the files.
+    // Remove any items in the project.levels Vec (and the levels of every
+    // world) ... useful when you get external file info and want to
+    // replace the items with more complete data extrated from the files.
     pub fn clear_levels(&mut self) {
         self.levels = Vec::new();
+        for world in self.worlds.iter_mut() {
+            world.levels = Vec::new();
+        }
     }
 
     // Read in ALL the external level files referred to in an LDTK Project
     pub fn load_external_levels<P: AsRef<Path>>(&mut self, f: P) {
+        self.try_load_external_levels(f)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    // Same as load_external_levels(), but reports a missing or broken level
+    // file instead of panicking. On error the levels are left untouched.
+    pub fn try_load_external_levels<P: AsRef<Path>>(&mut self, f: P) -> Result<(), LdtkError> {
+        let parent = f.as_ref().parent().unwrap_or_else(|| Path::new(""));
+        self.load_level_files(&FileSource::new(parent))
+    }
+
+    // The loaders that read from disk load the level files in parallel
+    // when the rayon feature is enabled.
+    #[cfg(feature = "rayon")]
+    fn load_level_files(&mut self, source: &FileSource) -> Result<(), LdtkError> {
+        self.try_load_external_levels_par(source)
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    fn load_level_files(&mut self, source: &FileSource) -> Result<(), LdtkError> {
+        self.try_load_external_levels_from(source)
+    }
+
+    // Same as try_load_external_levels(), reading the level files from an
+    // AssetSource rooted at the project file's directory.
+    pub fn try_load_external_levels_from<S: AssetSource + ?Sized>(
+        &mut self,
+        source: &S,
+    ) -> Result<(), LdtkError> {
+        self.try_load_external_levels_with_observer(source, &())
+    }
+
+    // Same as try_load_external_levels_from(), reporting progress to a
+    // LoadObserver.
+    pub fn try_load_external_levels_with_observer<S, O>(
+        &mut self,
+        source: &S,
+        observer: &O,
+    ) -> Result<(), LdtkError>
+    where
+        S: AssetSource + ?Sized,
+        O: LoadObserver + ?Sized,
+    {
         // check to make sure there ARE separate levels
         // if not, then likely the call to this method
         // should do nothing because you already have
         // the levels.
-        if self.external_levels {
-            // get all the file names
-            let mut all_level_files: Vec<PathBuf> = Vec::new();
-            for level in self.levels.iter_mut() {
-                let level_file_path = level.external_rel_path.as_ref().expect("missing level");
-                all_level_files.push(level_file_path.into());
-            }
-
-            // get rid of existing levels (which don't have much data)
-            self.clear_levels();
-
-            // now add each of them to our struct
-            for file in all_level_files.iter() {
-                let mut full_path = PathBuf::new();
-                let parent = f.as_ref().parent().unwrap().to_str().unwrap();
-                // println!("parent: {:#?}", parent);
-                let mf = file.to_str().unwrap();
-                full_path.push(format!("{parent}/{mf}"));
-                // full_path.push("/");
-                // full_path.push(&file);
-                println!("opening {:#?}", full_path);
-                let level_ldtk = Level::new(full_path);
-                self.levels.push(level_ldtk);
-            }
+        if !self.external_levels {
+            return Ok(());
         }
+
+        // now load each of them, then swap them in for the existing
+        // levels (which don't have much data)
+        let mut levels = Vec::new();
+        for (world, level) in self.iter_levels_with_world() {
+            levels.push(self.load_external_level(source, &world, level, observer)?.0);
+        }
+        self.replace_levels(levels);
+        Ok(())
     }
 
-    pub fn get_level(&self, uid: i64) -> Option<&Level> {
-        for level in self.levels.iter() {
-            if level.uid == uid {
-                return Some(level);
-            }
+    // Same as try_load_external_levels_from(), but reads and parses the
+    // level files on the rayon thread pool. The levels keep their order.
+    #[cfg(feature = "rayon")]
+    pub fn try_load_external_levels_par<S: AssetSource + Sync + ?Sized>(
+        &mut self,
+        source: &S,
+    ) -> Result<(), LdtkError> {
+        self.try_load_external_levels_par_with_observer(source, &())
+    }
+
+    // Same as try_load_external_levels_par(), reporting progress to a
+    // LoadObserver. Its methods get called from the rayon threads.
+    #[cfg(feature = "rayon")]
+    pub fn try_load_external_levels_par_with_observer<S, O>(
+        &mut self,
+        source: &S,
+        observer: &O,
+    ) -> Result<(), LdtkError>
+    where
+        S: AssetSource + Sync + ?Sized,
+        O: LoadObserver + Sync + ?Sized,
+    {
+        use rayon::prelude::*;
+
+        if !self.external_levels {
+            return Ok(());
         }
-        None
+        let stubs: Vec<(WorldRef<'_>, &Level)> = self.iter_levels_with_world().collect();
+        let levels = stubs
+            .into_par_iter()
+            .map(|(world, level)| {
+                self.load_external_level(source, &world, level, observer)
+                    .map(|(l, _)| l)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.replace_levels(levels);
+        Ok(())
+    }
+
+    // Swap fully loaded levels in for the stubs, in iter_levels() order.
+    fn replace_levels(&mut self, levels: Vec<Level>) {
+        for (stub, level) in self.iter_levels_mut().zip(levels) {
+            *stub = level;
+        }
+    }
+
+    // Load the full data for a level stub of `world` from its external
+    // file. Also returns the size of the file.
+    pub(crate) fn load_external_level<S, O>(
+        &self,
+        source: &S,
+        world: &WorldRef,
+        level: &Level,
+        observer: &O,
+    ) -> Result<(Level, usize), LdtkError>
+    where
+        S: AssetSource + ?Sized,
+        O: LoadObserver + ?Sized,
+    {
+        #[cfg(feature = "tracing")]
+        let _span = level_span(level).entered();
+        let rel_path = start_external_level(level, observer)?;
+        debug!("opening {}", source.location(rel_path).display());
+        let b = source
+            .read(rel_path)
+            .map_err(|e| external_level_error(level, e, source.location(rel_path)))?;
+        let full = self.finish_external_level(source, world, rel_path, &b, observer)?;
+        Ok((full, b.len()))
+    }
+
+    // What's left to do once a level stub's external file has been read,
+    // shared with the async loader.
+    pub(crate) fn finish_external_level<S, O>(
+        &self,
+        source: &S,
+        world: &WorldRef,
+        rel_path: &str,
+        b: &[u8],
+        observer: &O,
+    ) -> Result<Level, LdtkError>
+    where
+        S: AsyncAssetSource + ?Sized,
+        O: LoadObserver + ?Sized,
+    {
+        observer.bytes_read(rel_path, b.len());
+        let full = self.parse_external_level(world, b, || source.location(rel_path))?;
+        observer.level_finished(&full);
+        Ok(full)
+    }
+
+    // Parse the contents of the external file of a level of `world`. Level
+    // files from older versions of LDtk are upgraded along with the
+    // project, using the layout of the world.
+    pub(crate) fn parse_external_level<F: FnOnce() -> PathBuf>(
+        &self,
+        world: &WorldRef,
+        b: &[u8],
+        location: F,
+    ) -> Result<Level, LdtkError> {
+        let version = upgrade::SchemaVersion::from_json_version(&self.json_version);
+        let ctx = upgrade::LevelContext {
+            defs: Some(&self.defs),
+            world_layout: world.world_layout,
+        };
+        parse_level(b, version, &ctx).map_err(|e| LdtkError::from_json(e, Some(location())))
+    }
+
+    // Look up a level in any of the worlds.
+    pub fn get_level(&self, uid: i64) -> Option<&Level> {
+        self.iter_levels().find(|level| level.uid == uid)
+    }
+
+    pub fn get_level_by_iid(&self, iid: &str) -> Option<&Level> {
+        self.iter_levels().find(|level| level.iid == iid)
+    }
+
+    pub fn get_level_by_identifier(&self, identifier: &str) -> Option<&Level> {
+        self.iter_levels()
+            .find(|level| level.identifier == identifier)
     }
 }
 
 impl Level {
     // Read in a single external LDTK level file
     pub fn new<P: AsRef<Path>>(f: P) -> Self {
-        let file = BufReader::new(File::open(f).expect("level file not found"));
-        let o: Level = serde_json::from_reader(file).expect("error while reading");
-        o
+        Level::try_new(f).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    // Level files from older versions of LDtk are upgraded using the
+    // version in their header. Values LDtk copies from the project
+    // definitions (like entity sizes) can't be looked up here, so prefer
+    // Project::load_external_levels() for those files.
+    pub fn try_new<P: AsRef<Path>>(f: P) -> Result<Self, LdtkError> {
+        let path = f.as_ref();
+        let parent = path.parent().unwrap_or_else(|| Path::new(""));
+        let name = path.file_name().unwrap_or_default();
+        let name = name
+            .to_str()
+            .ok_or_else(|| LdtkError::NonUtf8Path(path.to_path_buf()))?;
+        Level::try_from_source(&FileSource::new(parent), name)
+    }
+
+    // Read a single external level file from an AssetSource.
+    pub fn try_from_source<S: AssetSource + ?Sized>(
+        source: &S,
+        f: &str,
+    ) -> Result<Self, LdtkError> {
+        let b = asset::read(source, f)?;
+        parse_standalone_level(&b).map_err(|e| LdtkError::from_json(e, Some(source.location(f))))
+    }
+}
+
+// Deserialize a project, going through the upgrade path if it was saved by
+// an older version of LDtk.
+fn parse_project(b: &[u8]) -> Result<Project, serde_json::Error> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("parse_project", bytes = b.len()).entered();
+    match upgrade::SchemaVersion::detect(b) {
+        Some(v) if v != upgrade::SchemaVersion::CURRENT => {
+            debug!("upgrading project saved by LDtk {v}");
+            upgrade::project(b, v)
+        }
+        _ => serde_json::from_slice(b),
+    }
+}
+
+// Deserialize a level file. `version` is used when the file doesn't say
+// which version of LDtk wrote it.
+fn parse_level(
+    b: &[u8],
+    version: upgrade::SchemaVersion,
+    ctx: &upgrade::LevelContext,
+) -> Result<Level, serde_json::Error> {
+    match upgrade::SchemaVersion::detect(b).unwrap_or(version) {
+        upgrade::SchemaVersion::CURRENT => serde_json::from_slice(b),
+        v => upgrade::level(b, v, ctx),
+    }
+}
+
+// Deserialize a level file read on its own, without the project it
+// belongs to.
+fn parse_standalone_level(b: &[u8]) -> Result<Level, serde_json::Error> {
+    let ctx = upgrade::LevelContext {
+        defs: None,
+        world_layout: None,
+    };
+    parse_level(b, upgrade::SchemaVersion::CURRENT, &ctx)
+}
+
+// What to do before reading a level stub's external file, shared with the
+// async loader. Returns the path of the file.
+pub(crate) fn start_external_level<'a, O: LoadObserver + ?Sized>(
+    level: &'a Level,
+    observer: &O,
+) -> Result<&'a str, LdtkError> {
+    observer.level_started(level);
+    external_rel_path(level)
+}
+
+// The span the loading of a level is reported in.
+#[cfg(feature = "tracing")]
+pub(crate) fn level_span(level: &Level) -> tracing::Span {
+    tracing::debug_span!("load_level", level = %level.identifier)
+}
+
+// The path of a level stub's external file.
+pub(crate) fn external_rel_path(level: &Level) -> Result<&str, LdtkError> {
+    level
+        .external_rel_path
+        .as_deref()
+        .ok_or_else(|| LdtkError::MissingExternalLevel {
+            identifier: level.identifier.clone(),
+            path: None,
+        })
+}
+
+// Tag an error reading a level stub's external file, a missing file being
+// reported as a missing level.
+pub(crate) fn external_level_error(level: &Level, e: std::io::Error, path: PathBuf) -> LdtkError {
+    match e.kind() {
+        std::io::ErrorKind::NotFound => LdtkError::MissingExternalLevel {
+            identifier: level.identifier.clone(),
+            path: Some(path),
+        },
+        _ => LdtkError::from_io(e, Some(path)),
     }
 }
 
@@ -115,6 +571,7 @@ pub struct LdtkJson;
 // some QuickType examples over at LDTK.
 #[allow(deprecated)]
 impl LdtkJson {
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(f: String) -> Project {
         Project::new(f)
     }
@@ -0,0 +1,44 @@
+// Diagnostics while loading. The loaders report what they are doing to
+// the `log` and/or `tracing` crates when those features are enabled, and
+// to a LoadObserver passed in by the caller.
+
+use crate::{Level, Project};
+
+/// Receives events while a project and its levels load. Every method does
+/// nothing by default, implement the ones you need. `()` is the observer
+/// that ignores everything.
+///
+/// `Project::try_load_external_levels_par_with_observer()` (with the
+/// `rayon` feature) loads levels on several threads at once, so the
+/// methods take `&self`; use a `Mutex` or atomics to keep state.
+pub trait LoadObserver {
+    /// The project file was parsed. External levels are still stubs.
+    fn project_parsed(&self, _project: &Project) {}
+
+    /// About to load the external file of this level stub.
+    fn level_started(&self, _stub: &Level) {}
+
+    /// The level was loaded from its external file.
+    fn level_finished(&self, _level: &Level) {}
+
+    /// A file was read. `path` is relative to the project, as given to the
+    /// AssetSource.
+    fn bytes_read(&self, _path: &str, _bytes: usize) {}
+}
+
+impl LoadObserver for () {}
+
+// Debug level message for the `log` and `tracing` features. Expands to
+// nothing when neither is enabled. With both, the message only goes to
+// tracing (which can forward to log with its own `log` feature) so it
+// isn't reported twice.
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        #[cfg(all(feature = "log", not(feature = "tracing")))]
+        log::debug!($($arg)*);
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+    };
+}
+
+pub(crate) use debug;
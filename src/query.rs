@@ -0,0 +1,136 @@
+// Finding entities across a whole project: every entity of every entity
+// layer of every loaded level of every world, narrowed down with filters.
+
+use crate::{EntityLocation, FieldAccess, Project};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fmt;
+
+type Filter<'a> = Box<dyn Fn(&EntityLocation<'a>) -> bool + 'a>;
+
+/// An iterator over the entities of a project, with their layer, level
+/// and world, that only yields the ones passing every filter. Created by
+/// [`Project::entities`].
+///
+/// ```ignore
+/// let bosses = project
+///     .entities()
+///     .with_identifier("Enemy")
+///     .with_tag("boss")
+///     .in_world("Overworld")
+///     .where_field("level", |v: i64| v >= 3);
+/// for e in bosses {
+///     println!("{} in {}", e.entity.iid, e.level.identifier);
+/// }
+/// ```
+///
+/// Entities of external levels that aren't loaded are not included.
+pub struct EntityQuery<'a> {
+    entities: Box<dyn Iterator<Item = EntityLocation<'a>> + 'a>,
+    filters: Vec<Filter<'a>>,
+}
+
+impl<'a> EntityQuery<'a> {
+    fn new(project: &'a Project) -> Self {
+        let entities = project.iter_worlds().flat_map(|world| {
+            world.levels.iter().flat_map(move |level| {
+                level
+                    .layer_instances
+                    .iter()
+                    .flatten()
+                    .flat_map(move |layer| {
+                        layer
+                            .entity_instances
+                            .iter()
+                            .map(move |entity| EntityLocation {
+                                entity,
+                                layer,
+                                level,
+                                world,
+                            })
+                    })
+            })
+        });
+        EntityQuery {
+            entities: Box::new(entities),
+            filters: Vec::new(),
+        }
+    }
+
+    /// Keep the entities for which `f` returns true.
+    pub fn matching<F: Fn(&EntityLocation<'a>) -> bool + 'a>(mut self, f: F) -> Self {
+        self.filters.push(Box::new(f));
+        self
+    }
+
+    pub fn with_identifier(self, identifier: &str) -> Self {
+        let identifier = identifier.to_string();
+        self.matching(move |e| e.entity.identifier == identifier)
+    }
+
+    pub fn with_tag(self, tag: &str) -> Self {
+        let tag = tag.to_string();
+        self.matching(move |e| e.entity.tags.contains(&tag))
+    }
+
+    /// Only entities in the world with this identifier.
+    pub fn in_world(self, identifier: &str) -> Self {
+        let identifier = identifier.to_string();
+        self.matching(move |e| e.world.identifier == identifier)
+    }
+
+    /// Only entities in the level with this identifier.
+    pub fn in_level(self, identifier: &str) -> Self {
+        let identifier = identifier.to_string();
+        self.matching(move |e| e.level.identifier == identifier)
+    }
+
+    /// Only entities on layers with this identifier.
+    pub fn on_layer(self, identifier: &str) -> Self {
+        let identifier = identifier.to_string();
+        self.matching(move |e| e.layer.identifier == identifier)
+    }
+
+    /// Only entities that have the field `identifier` and whose value,
+    /// deserialized as a `T`, passes `f`. Entities where the value can't
+    /// be read as a `T` (because it has another type, or is null and `T`
+    /// isn't an `Option`) are left out.
+    pub fn where_field<T, F>(self, identifier: &str, f: F) -> Self
+    where
+        T: DeserializeOwned,
+        F: Fn(T) -> bool + 'a,
+    {
+        let identifier = identifier.to_string();
+        self.matching(move |e| {
+            e.entity.field(&identifier).is_some_and(|field| {
+                let value = field.value.as_ref().unwrap_or(&Value::Null);
+                T::deserialize(value).is_ok_and(&f)
+            })
+        })
+    }
+}
+
+impl<'a> Iterator for EntityQuery<'a> {
+    type Item = EntityLocation<'a>;
+
+    fn next(&mut self) -> Option<EntityLocation<'a>> {
+        let filters = &self.filters;
+        self.entities.find(|e| filters.iter().all(|f| f(e)))
+    }
+}
+
+impl fmt::Debug for EntityQuery<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EntityQuery")
+            .field("filters", &self.filters.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Project {
+    /// Every entity of the project, see [`EntityQuery`] for narrowing
+    /// them down.
+    pub fn entities(&self) -> EntityQuery<'_> {
+        EntityQuery::new(self)
+    }
+}
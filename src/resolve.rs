@@ -0,0 +1,71 @@
+// Entity references (EntityRef field values, table of contents entries)
+// only hold the iids of the entity and of the layer, level and world it
+// is in. This finds the entity they point to, loading the level it is in
+// first if that level is still a stub.
+
+use crate::{AssetSource, EntityLocation, EntityRef, LdtkError, Level, Project, WorldRef};
+
+impl Project {
+    /// The entity a reference points to, with its layer, level and world.
+    /// Returns None if the entity isn't there, which includes entities in
+    /// external levels that aren't loaded yet (see
+    /// [`Project::try_resolve_from`]).
+    pub fn resolve(&self, r: &EntityRef) -> Option<EntityLocation<'_>> {
+        let world = self.world_of_level(&r.level_iid)?;
+        let level = world.levels.iter().find(|l| l.iid == r.level_iid)?;
+        locate(world, level, r)
+    }
+
+    pub fn resolve_from<S: AssetSource + ?Sized>(
+        &mut self,
+        source: &S,
+        r: &EntityRef,
+    ) -> Option<EntityLocation<'_>> {
+        self.try_resolve_from(source, r)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Same as [`Project::resolve`], but if the entity's level is an
+    /// external level that isn't loaded yet, it is loaded from `source`
+    /// (rooted at the project file's directory) and kept in the project.
+    pub fn try_resolve_from<S: AssetSource + ?Sized>(
+        &mut self,
+        source: &S,
+        r: &EntityRef,
+    ) -> Result<Option<EntityLocation<'_>>, LdtkError> {
+        if let Some(world) = self.world_of_level(&r.level_iid)
+            && let Some(stub) = world.levels.iter().find(|l| l.iid == r.level_iid)
+            && self.external_levels
+            && stub.layer_instances.is_none()
+        {
+            let (level, _) = self.load_external_level(source, &world, stub, &())?;
+            if let Some(stub) = self.iter_levels_mut().find(|l| l.iid == r.level_iid) {
+                *stub = level;
+            }
+        }
+        Ok(self.resolve(r))
+    }
+}
+
+// Find the referenced entity in a loaded level.
+pub(crate) fn locate<'a>(
+    world: WorldRef<'a>,
+    level: &'a Level,
+    r: &EntityRef,
+) -> Option<EntityLocation<'a>> {
+    let layer = level
+        .layer_instances
+        .iter()
+        .flatten()
+        .find(|l| l.iid == r.layer_iid)?;
+    let entity = layer
+        .entity_instances
+        .iter()
+        .find(|e| e.iid == r.entity_iid)?;
+    Some(EntityLocation {
+        entity,
+        layer,
+        level,
+        world,
+    })
+}
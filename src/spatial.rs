@@ -0,0 +1,501 @@
+// Spatial queries over the levels and entities of a world. Everything is
+// bucketed into a uniform grid of square cells in world pixels, so a query
+// only looks at the cells it touches instead of every level and entity.
+
+use crate::{EntityInstance, EntityLocation, LayerInstance, Level, WorldRef};
+use std::collections::HashMap;
+
+// cell size used by SpatialIndex::new()
+const DEFAULT_CELL_SIZE: i64 = 256;
+
+/// An axis aligned rectangle in pixels. It covers `x..x + width` and
+/// `y..y + height`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rect {
+    pub x: i64,
+    pub y: i64,
+    pub width: i64,
+    pub height: i64,
+}
+
+impl Rect {
+    pub fn new(x: i64, y: i64, width: i64, height: i64) -> Self {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn right(&self) -> i64 {
+        self.x + self.width
+    }
+
+    pub fn bottom(&self) -> i64 {
+        self.y + self.height
+    }
+
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x as f64
+            && x < self.right() as f64
+            && y >= self.y as f64
+            && y < self.bottom() as f64
+    }
+
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.x < other.right()
+            && other.x < self.right()
+            && self.y < other.bottom()
+            && other.y < self.bottom()
+    }
+
+    /// Distance from a point to the closest point of the rectangle, 0 if
+    /// the point is inside.
+    pub fn distance_to(&self, x: f64, y: f64) -> f64 {
+        let dx = (self.x as f64 - x).max(x - self.right() as f64).max(0.0);
+        let dy = (self.y as f64 - y).max(y - self.bottom() as f64).max(0.0);
+        dx.hypot(dy)
+    }
+}
+
+/// Finds the levels and entities of a world by position, in world pixels.
+///
+/// ```ignore
+/// let world = project.get_world_by_identifier("Overworld").unwrap();
+/// let index = world.spatial_index();
+/// let here = index.level_at(player.x, player.y);
+/// let seen = index.entities_in_circle(player.x, player.y, 200.0);
+/// let exit = index.nearest("Exit", player.x, player.y);
+/// ```
+///
+/// Built from the loaded levels only. Levels of `LinearHorizontal` and
+/// `LinearVertical` worlds are laid out edge to edge, as
+/// [`WorldRef::iter_level_bounds`] does.
+#[derive(Debug, Clone)]
+pub struct SpatialIndex<'a> {
+    cell_size: i64,
+    levels: Vec<(&'a Level, Rect)>,
+    level_cells: HashMap<(i64, i64), Vec<usize>>,
+    entities: Vec<Entry<'a>>,
+    entity_cells: HashMap<(i64, i64), Vec<usize>>,
+    // the cells holding levels and entities, so that queries don't look
+    // at cells that are bound to be empty
+    level_extent: Cells,
+    entity_extent: Cells,
+}
+
+// A range of cells: the first and last column, the first and last row.
+// Empty if first > last.
+#[derive(Debug, Clone, Copy)]
+struct Cells {
+    x0: i64,
+    y0: i64,
+    x1: i64,
+    y1: i64,
+}
+
+impl Cells {
+    const EMPTY: Cells = Cells {
+        x0: 0,
+        y0: 0,
+        x1: -1,
+        y1: -1,
+    };
+
+    fn is_empty(&self) -> bool {
+        self.x0 > self.x1 || self.y0 > self.y1
+    }
+
+    fn contains(&self, (x, y): (i64, i64)) -> bool {
+        (self.x0..=self.x1).contains(&x) && (self.y0..=self.y1).contains(&y)
+    }
+
+    fn union(self, other: Cells) -> Cells {
+        if self.is_empty() {
+            return other;
+        }
+        if other.is_empty() {
+            return self;
+        }
+        Cells {
+            x0: self.x0.min(other.x0),
+            y0: self.y0.min(other.y0),
+            x1: self.x1.max(other.x1),
+            y1: self.y1.max(other.y1),
+        }
+    }
+
+    fn intersection(self, other: Cells) -> Cells {
+        Cells {
+            x0: self.x0.max(other.x0),
+            y0: self.y0.max(other.y0),
+            x1: self.x1.min(other.x1),
+            y1: self.y1.min(other.y1),
+        }
+    }
+
+    fn iter(self) -> impl Iterator<Item = (i64, i64)> {
+        (self.y0..=self.y1).flat_map(move |y| (self.x0..=self.x1).map(move |x| (x, y)))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Entry<'a> {
+    location: EntityLocation<'a>,
+    bounds: Rect,
+    // the entity's pivot, in world pixels
+    position: (f64, f64),
+}
+
+impl<'a> SpatialIndex<'a> {
+    pub fn new(world: WorldRef<'a>) -> Self {
+        SpatialIndex::with_cell_size(world, DEFAULT_CELL_SIZE)
+    }
+
+    /// Use cells of `cell_size` pixels. Around the size of the queried
+    /// areas works best.
+    pub fn with_cell_size(world: WorldRef<'a>, cell_size: i64) -> Self {
+        let mut index = SpatialIndex {
+            cell_size: cell_size.max(1),
+            levels: Vec::new(),
+            level_cells: HashMap::new(),
+            entities: Vec::new(),
+            entity_cells: HashMap::new(),
+            level_extent: Cells::EMPTY,
+            entity_extent: Cells::EMPTY,
+        };
+        for (level, level_bounds) in world.iter_level_bounds() {
+            let cells = index.cells(&level_bounds);
+            index.level_extent = index.level_extent.union(cells);
+            for cell in cells.iter() {
+                index
+                    .level_cells
+                    .entry(cell)
+                    .or_default()
+                    .push(index.levels.len());
+            }
+            index.levels.push((level, level_bounds));
+
+            for layer in level.layer_instances.iter().flatten() {
+                for entity in &layer.entity_instances {
+                    let (bounds, position) = entity_bounds(&level_bounds, layer, entity);
+                    let cells = index.cells(&bounds);
+                    index.entity_extent = index.entity_extent.union(cells);
+                    for cell in cells.iter() {
+                        index
+                            .entity_cells
+                            .entry(cell)
+                            .or_default()
+                            .push(index.entities.len());
+                    }
+                    index.entities.push(Entry {
+                        location: EntityLocation {
+                            entity,
+                            layer,
+                            level,
+                            world,
+                        },
+                        bounds,
+                        position,
+                    });
+                }
+            }
+        }
+        index
+    }
+
+    /// The level containing a point. If levels overlap, the first one in
+    /// the world's order.
+    pub fn level_at(&self, x: f64, y: f64) -> Option<&'a Level> {
+        let cell = self.cell_of(x, y);
+        if !self.level_extent.contains(cell) {
+            return None;
+        }
+        self.level_cells
+            .get(&cell)?
+            .iter()
+            .map(|&i| self.levels[i])
+            .find(|(_, bounds)| bounds.contains(x, y))
+            .map(|(level, _)| level)
+    }
+
+    /// The levels intersecting a rectangle, in the world's order.
+    pub fn levels_in_rect(&self, rect: Rect) -> Vec<&'a Level> {
+        let cells = self.cells(&rect).intersection(self.level_extent);
+        candidates(&self.level_cells, cells)
+            .into_iter()
+            .map(|i| self.levels[i])
+            .filter(|(_, bounds)| bounds.intersects(&rect))
+            .map(|(level, _)| level)
+            .collect()
+    }
+
+    /// The entities whose bounds intersect a rectangle, in the order of
+    /// their levels and layers.
+    pub fn entities_in_rect(&self, rect: Rect) -> Vec<EntityLocation<'a>> {
+        let cells = self.cells(&rect).intersection(self.entity_extent);
+        candidates(&self.entity_cells, cells)
+            .into_iter()
+            .map(|i| &self.entities[i])
+            .filter(|e| e.bounds.intersects(&rect))
+            .map(|e| e.location)
+            .collect()
+    }
+
+    /// The entities whose bounds intersect a circle, in the order of their
+    /// levels and layers.
+    pub fn entities_in_circle(&self, x: f64, y: f64, radius: f64) -> Vec<EntityLocation<'a>> {
+        let around = Rect::new(
+            (x - radius).floor() as i64,
+            (y - radius).floor() as i64,
+            (2.0 * radius).ceil() as i64 + 1,
+            (2.0 * radius).ceil() as i64 + 1,
+        );
+        let cells = self.cells(&around).intersection(self.entity_extent);
+        candidates(&self.entity_cells, cells)
+            .into_iter()
+            .map(|i| &self.entities[i])
+            .filter(|e| e.bounds.distance_to(x, y) <= radius)
+            .map(|e| e.location)
+            .collect()
+    }
+
+    /// The entity with this identifier whose position (its pivot) is the
+    /// closest to a point.
+    pub fn nearest(&self, identifier: &str, x: f64, y: f64) -> Option<EntityLocation<'a>> {
+        let extent = self.entity_extent;
+        if extent.is_empty() {
+            return None;
+        }
+        let Cells { x0, y0, x1, y1 } = extent;
+        let (cx, cy) = self.cell_of(x, y);
+        let mut best: Option<(f64, usize)> = None;
+
+        // look at rings of cells around the point's cell, starting with the
+        // first one that reaches the cells with entities, until no cell of
+        // the ring can be closer than the best entity found so far
+        let first = (x0 - cx).max(cx - x1).max(y0 - cy).max(cy - y1).max(0);
+        for ring in first.. {
+            if let Some((distance, _)) = best
+                && ((ring - 1) * self.cell_size) as f64 > distance
+            {
+                break;
+            }
+            for cell in ring_cells(cx, cy, ring, extent) {
+                for &i in self.entity_cells.get(&cell).into_iter().flatten() {
+                    let e = &self.entities[i];
+                    if e.location.entity.identifier != identifier {
+                        continue;
+                    }
+                    let distance = (e.position.0 - x).hypot(e.position.1 - y);
+                    if best.is_none_or(|(d, j)| distance < d || (distance == d && i < j)) {
+                        best = Some((distance, i));
+                    }
+                }
+            }
+            if cx - ring <= x0 && cx + ring >= x1 && cy - ring <= y0 && cy + ring >= y1 {
+                break;
+            }
+        }
+        best.map(|(_, i)| self.entities[i].location)
+    }
+
+    fn cell_of(&self, x: f64, y: f64) -> (i64, i64) {
+        let size = self.cell_size as f64;
+        ((x / size).floor() as i64, (y / size).floor() as i64)
+    }
+
+    // The cells a rectangle covers. Empty rectangles still cover the cell
+    // they are in.
+    fn cells(&self, rect: &Rect) -> Cells {
+        let size = self.cell_size;
+        Cells {
+            x0: rect.x.div_euclid(size),
+            y0: rect.y.div_euclid(size),
+            x1: (rect.right() - 1).max(rect.x).div_euclid(size),
+            y1: (rect.bottom() - 1).max(rect.y).div_euclid(size),
+        }
+    }
+}
+
+impl<'a> WorldRef<'a> {
+    /// Build a [`SpatialIndex`] of the world.
+    pub fn spatial_index(self) -> SpatialIndex<'a> {
+        SpatialIndex::new(self)
+    }
+}
+
+// The items in some cells, without duplicates and in the order they were
+// added to the index.
+fn candidates(buckets: &HashMap<(i64, i64), Vec<usize>>, cells: Cells) -> Vec<usize> {
+    let mut found: Vec<usize> = cells
+        .iter()
+        .filter_map(|cell| buckets.get(&cell))
+        .flatten()
+        .copied()
+        .collect();
+    found.sort_unstable();
+    found.dedup();
+    found
+}
+
+// The cells at a Chebyshev distance of `ring` from (cx, cy) that are
+// within `extent`.
+fn ring_cells(cx: i64, cy: i64, ring: i64, extent: Cells) -> impl Iterator<Item = (i64, i64)> {
+    let (top, bottom) = (cy - ring, cy + ring);
+    let (left, right) = (cx - ring, cx + ring);
+    let row = |y: i64| Cells {
+        x0: left,
+        y0: y,
+        x1: right,
+        y1: y,
+    };
+    let column = |x: i64| Cells {
+        x0: x,
+        y0: top + 1,
+        x1: x,
+        y1: bottom - 1,
+    };
+    let mut sides = vec![row(top)];
+    if ring > 0 {
+        sides.extend([row(bottom), column(left), column(right)]);
+    }
+    sides
+        .into_iter()
+        .flat_map(move |side| side.intersection(extent).iter())
+}
+
+// An entity's bounds and pivot in world pixels, `level` being the bounds
+// of its level. Same as EntityInstance::world_bounds(), without looking
+// for the level in its world. Entities without a size still cover a
+// pixel, so that they end up in a cell.
+fn entity_bounds(
+    level: &Rect,
+    layer: &LayerInstance,
+    entity: &EntityInstance,
+) -> (Rect, (f64, f64)) {
+    let (lx, ly) = entity.level_px(layer);
+    let x = entity.world_x.unwrap_or(lx + level.x);
+    let y = entity.world_y.unwrap_or(ly + level.y);
+    let mut bounds = entity.level_bounds(layer);
+    bounds.x += x - lx;
+    bounds.y += y - ly;
+    bounds.width = bounds.width.max(1);
+    bounds.height = bounds.height.max(1);
+    (bounds, (x as f64, y as f64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Project;
+
+    // game_1-1-3.ldtk, a LinearHorizontal world, with its level (368 by 288
+    // pixels) twice and 8 by 8 pixel birds at these level px in both.
+    fn project(birds: &[(i64, i64)]) -> Project {
+        let mut project = Project::from_slice(include_bytes!("../assets/game_1-1-3.ldtk"));
+        let mut level = project.levels[0].clone();
+        let layer = &mut level.layer_instances.as_mut().unwrap()[0];
+        let bird = layer
+            .entity_instances
+            .iter()
+            .find(|e| e.identifier == "Bird")
+            .unwrap()
+            .clone();
+        layer.entity_instances = birds
+            .iter()
+            .map(|&(x, y)| {
+                let mut e = bird.clone();
+                e.px = vec![x, y];
+                e.iid = format!("{}-{x}-{y}", level.iid);
+                e
+            })
+            .collect();
+        let mut second = level.clone();
+        second.iid = "second".to_string();
+        second.identifier = "Second".to_string();
+        project.levels = vec![level, second];
+        project
+    }
+
+    #[test]
+    fn linear_worlds_are_indexed() {
+        let project = project(&[(80, 8)]);
+        let index = project.iter_worlds().next().unwrap().spatial_index();
+        assert_eq!(index.level_at(10.0, 10.0).unwrap().identifier, "Level");
+        assert_eq!(index.level_at(400.0, 10.0).unwrap().identifier, "Second");
+        assert!(index.level_at(-1.0, -1.0).is_none());
+        let found = index.nearest("Bird", 450.0, 10.0).unwrap();
+        assert_eq!(found.level.identifier, "Second");
+        let found = index.entities_in_rect(Rect::new(368 + 80, 8, 1, 1));
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].level.identifier, "Second");
+    }
+
+    #[test]
+    fn nearest_on_a_ring_boundary() {
+        // cells of 16 pixels, the query point on a cell corner: the bird
+        // at 48, 16 is exactly two cells (32 pixels) away, on the edge of
+        // the second ring
+        let project = project(&[(48, 16), (16, 49)]);
+        let world = project.iter_worlds().next().unwrap();
+        let index = SpatialIndex::with_cell_size(world, 16);
+        let found = index.nearest("Bird", 16.0, 16.0).unwrap();
+        assert_eq!(found.entity.px, [48, 16]);
+        assert_eq!(found.level.identifier, "Level");
+
+        // the same as looking at every bird, from all over the world
+        let birds: Vec<(f64, f64)> = [(48, 16), (16, 49)]
+            .iter()
+            .flat_map(|&(x, y)| [(x, y), (x + 368, y)])
+            .map(|(x, y)| (x as f64, y as f64))
+            .collect();
+        for y in (-64..352).step_by(16) {
+            for x in (-64..800).step_by(16) {
+                let (x, y) = (x as f64, y as f64);
+                let found = index.nearest("Bird", x, y).unwrap();
+                let (fx, fy) = found
+                    .entity
+                    .world_px(found.layer, found.level, &world)
+                    .unwrap();
+                let distance = (fx as f64 - x).hypot(fy as f64 - y);
+                let closest = birds
+                    .iter()
+                    .map(|&(bx, by)| (bx - x).hypot(by - y))
+                    .fold(f64::INFINITY, f64::min);
+                assert_eq!(distance, closest, "from {x}, {y}");
+            }
+        }
+    }
+
+    #[test]
+    fn nearest_without_entities() {
+        let empty = project(&[]);
+        let index = empty.iter_worlds().next().unwrap().spatial_index();
+        assert!(index.nearest("Bird", 0.0, 0.0).is_none());
+        assert!(index.entities_in_circle(0.0, 0.0, 1000.0).is_empty());
+
+        let birds = project(&[(0, 0)]);
+        let index = birds.iter_worlds().next().unwrap().spatial_index();
+        assert!(index.nearest("Hero", 0.0, 0.0).is_none());
+    }
+
+    #[test]
+    fn entities_in_circle() {
+        // the first bird covers 32..40, 0..8
+        let project = project(&[(32, 0), (100, 100)]);
+        let index = project.iter_worlds().next().unwrap().spatial_index();
+        let px = |found: Vec<EntityLocation>| -> Vec<Vec<i64>> {
+            found.iter().map(|e| e.entity.px.clone()).collect()
+        };
+        assert_eq!(px(index.entities_in_circle(0.0, 4.0, 32.0)), [[32, 0]]);
+        assert!(index.entities_in_circle(0.0, 4.0, 31.9).is_empty());
+        // inside the bird
+        assert_eq!(px(index.entities_in_circle(36.0, 4.0, 0.0)), [[32, 0]]);
+        assert_eq!(
+            px(index.entities_in_circle(60.0, 60.0, 60.0)),
+            [[32, 0], [100, 100]]
+        );
+    }
+}
@@ -0,0 +1,357 @@
+// Keeps the level stubs of a project with external level files and loads
+// the full levels from their .ldtkl files the first time they are asked
+// for. Loaded levels are cached and, once the cache goes over its budget,
+// the least recently used ones are dropped again.
+
+use crate::{
+    AssetSource, EntityLocation, EntityRef, LdtkError, Level, LoadObserver, Project, WorldRef,
+    asset, parse_project, resolve,
+};
+
+/// Loads the levels of a project on demand.
+///
+/// ```ignore
+/// let mut store = LevelStore::from_source(FileSource::new("assets"), "game.ldtk")?
+///     .with_max_levels(9);
+/// if let Some(level) = store.get_by_identifier("Level_0")? {
+///     println!("{} layers", level.layer_instances.as_ref().map_or(0, |l| l.len()));
+/// }
+/// ```
+///
+/// Levels of every world are covered. Projects that keep their levels in
+/// the project file work too, their levels are simply handed out as they
+/// are.
+pub struct LevelStore<S> {
+    project: Project,
+    source: S,
+    observer: Box<dyn LoadObserver + Send + Sync>,
+    slots: Vec<Slot>,
+    max_levels: Option<usize>,
+    max_bytes: Option<usize>,
+    loaded_levels: usize,
+    loaded_bytes: usize,
+    // bumped on every access, used to find the least recently used level
+    clock: u64,
+}
+
+#[derive(Default)]
+struct Slot {
+    level: Option<Level>,
+    bytes: usize,
+    last_used: u64,
+}
+
+impl<S: AssetSource> LevelStore<S> {
+    /// Use the levels of an already loaded project. `source` must be rooted
+    /// at the directory of the project file. Levels the project already
+    /// has loaded are not reused, only their stubs are looked at.
+    pub fn new(project: Project, source: S) -> Self {
+        let mut slots = Vec::new();
+        slots.resize_with(project.iter_levels().count(), Slot::default);
+        LevelStore {
+            project,
+            source,
+            observer: Box::new(()),
+            slots,
+            max_levels: None,
+            max_bytes: None,
+            loaded_levels: 0,
+            loaded_bytes: 0,
+            clock: 0,
+        }
+    }
+
+    /// Read the project file `f` from `source` without loading any level.
+    pub fn from_source(source: S, f: &str) -> Result<Self, LdtkError> {
+        let b = asset::read(&source, f)?;
+        let project =
+            parse_project(&b).map_err(|e| LdtkError::from_json(e, Some(source.location(f))))?;
+        Ok(LevelStore::new(project, source))
+    }
+
+    /// Keep at most `n` levels loaded (at least one is always kept).
+    pub fn with_max_levels(mut self, n: usize) -> Self {
+        self.max_levels = Some(n);
+        self.evict(None);
+        self
+    }
+
+    /// Keep the levels loaded under roughly `bytes` bytes, measured by the
+    /// size of their level files (at least one is always kept).
+    pub fn with_max_bytes(mut self, bytes: usize) -> Self {
+        self.max_bytes = Some(bytes);
+        self.evict(None);
+        self
+    }
+
+    /// Report the levels the store loads to `observer`.
+    pub fn with_observer<O: LoadObserver + Send + Sync + 'static>(mut self, observer: O) -> Self {
+        self.observer = Box::new(observer);
+        self
+    }
+
+    /// The project, with the stubs of its levels.
+    pub fn project(&self) -> &Project {
+        &self.project
+    }
+
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    /// Get the level with this uid, loading it if needed. Returns None if
+    /// the project has no such level.
+    pub fn get(&mut self, uid: i64) -> Result<Option<&Level>, LdtkError> {
+        self.get_where(|l| l.uid == uid)
+    }
+
+    pub fn get_by_iid(&mut self, iid: &str) -> Result<Option<&Level>, LdtkError> {
+        self.get_where(|l| l.iid == iid)
+    }
+
+    pub fn get_by_identifier(&mut self, identifier: &str) -> Result<Option<&Level>, LdtkError> {
+        self.get_where(|l| l.identifier == identifier)
+    }
+
+    /// The entity a reference points to, with its layer, level and world,
+    /// loading the entity's level if needed. The level counts as used like
+    /// with [`LevelStore::get`].
+    pub fn resolve(&mut self, r: &EntityRef) -> Result<Option<EntityLocation<'_>>, LdtkError> {
+        let Some(i) = self.position(|l| l.iid == r.level_iid) else {
+            return Ok(None);
+        };
+        self.load(i)?;
+        let (world, stub) = self.stub(i);
+        let level = self.slots[i].level.as_ref().unwrap_or(stub);
+        Ok(resolve::locate(world, level, r))
+    }
+
+    fn get_where<F: Fn(&Level) -> bool>(&mut self, f: F) -> Result<Option<&Level>, LdtkError> {
+        match self.position(f) {
+            Some(i) => self.load(i).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn position<F: Fn(&Level) -> bool>(&self, f: F) -> Option<usize> {
+        self.project.iter_levels().position(f)
+    }
+
+    // The stub of the i-th level, with its world.
+    fn stub(&self, i: usize) -> (WorldRef<'_>, &Level) {
+        self.project
+            .iter_levels_with_world()
+            .nth(i)
+            .expect("index of a level")
+    }
+
+    fn load(&mut self, i: usize) -> Result<&Level, LdtkError> {
+        if !self.project.external_levels {
+            return Ok(self.stub(i).1);
+        }
+        self.clock += 1;
+        if self.slots[i].level.is_none() {
+            let (world, stub) = self.stub(i);
+            let (level, bytes) =
+                self.project
+                    .load_external_level(&self.source, &world, stub, &*self.observer)?;
+            self.slots[i].level = Some(level);
+            self.slots[i].bytes = bytes;
+            self.loaded_levels += 1;
+            self.loaded_bytes += bytes;
+            self.evict(Some(i));
+        }
+        let slot = &mut self.slots[i];
+        slot.last_used = self.clock;
+        Ok(slot.level.as_ref().expect("level was just loaded"))
+    }
+
+    /// Whether the level with this uid is currently loaded.
+    pub fn is_loaded(&self, uid: i64) -> bool {
+        match self.position(|l| l.uid == uid) {
+            Some(_) if !self.project.external_levels => true,
+            Some(i) => self.slots[i].level.is_some(),
+            None => false,
+        }
+    }
+
+    /// Drop the loaded data of a level. Returns false if it wasn't loaded.
+    pub fn unload(&mut self, uid: i64) -> bool {
+        match self.position(|l| l.uid == uid) {
+            Some(i) => self.unload_slot(i),
+            None => false,
+        }
+    }
+
+    pub fn unload_all(&mut self) {
+        for i in 0..self.slots.len() {
+            self.unload_slot(i);
+        }
+    }
+
+    /// Number of levels currently loaded from their level files.
+    pub fn loaded_levels(&self) -> usize {
+        self.loaded_levels
+    }
+
+    /// Size of the level files of the levels currently loaded.
+    pub fn loaded_bytes(&self) -> usize {
+        self.loaded_bytes
+    }
+
+    fn unload_slot(&mut self, i: usize) -> bool {
+        let slot = &mut self.slots[i];
+        if slot.level.take().is_none() {
+            return false;
+        }
+        self.loaded_levels -= 1;
+        self.loaded_bytes -= slot.bytes;
+        slot.bytes = 0;
+        true
+    }
+
+    fn over_budget(&self) -> bool {
+        self.loaded_levels > 1
+            && (self.max_levels.is_some_and(|n| self.loaded_levels > n)
+                || self.max_bytes.is_some_and(|n| self.loaded_bytes > n))
+    }
+
+    // Drop least recently used levels until the store is back under its
+    // budget, never dropping `keep` (the level that was just loaded).
+    fn evict(&mut self, keep: Option<usize>) {
+        while self.over_budget() {
+            let lru = self
+                .slots
+                .iter()
+                .enumerate()
+                .filter(|(i, s)| s.level.is_some() && Some(*i) != keep)
+                .min_by_key(|(_, s)| s.last_used)
+                .map(|(i, _)| i);
+            match lru {
+                Some(i) => self.unload_slot(i),
+                None => break,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemorySource;
+
+    // Level uids of SeparateLevelFiles.ldtk
+    const A: i64 = 0;
+    const B: i64 = 2;
+    const C: i64 = 3;
+
+    fn source() -> MemorySource {
+        let mut source = MemorySource::new();
+        source.insert(
+            "game.ldtk",
+            include_bytes!("../assets/SeparateLevelFiles.ldtk").to_vec(),
+        );
+        for level in ["0000-Level_A", "0001-Level_B", "0002-Level_C"] {
+            let b = std::fs::read(format!("assets/SeparateLevelFiles/{level}.ldtkl")).unwrap();
+            source.insert(format!("SeparateLevelFiles/{level}.ldtkl"), b);
+        }
+        source
+    }
+
+    fn store() -> LevelStore<MemorySource> {
+        LevelStore::from_source(source(), "game.ldtk").unwrap()
+    }
+
+    fn loaded(store: &LevelStore<MemorySource>) -> [bool; 3] {
+        [A, B, C].map(|uid| store.is_loaded(uid))
+    }
+
+    #[test]
+    fn loads_on_demand() {
+        let mut store = store();
+        assert_eq!(loaded(&store), [false; 3]);
+        let level = store.get_by_identifier("Level_B").unwrap().unwrap();
+        assert_eq!(level.uid, B);
+        assert!(level.layer_instances.is_some());
+        assert_eq!(loaded(&store), [false, true, false]);
+        assert_eq!(store.loaded_levels(), 1);
+        assert_eq!(store.loaded_bytes(), 2013);
+        assert!(store.get(42).unwrap().is_none());
+        assert!(!store.is_loaded(42));
+    }
+
+    #[test]
+    fn evicts_the_least_recently_used_level() {
+        let mut store = store().with_max_levels(2);
+        store.get(A).unwrap();
+        store.get(B).unwrap();
+        // A is used again, so B is the one to go
+        store.get(A).unwrap();
+        store.get(C).unwrap();
+        assert_eq!(loaded(&store), [true, false, true]);
+        assert_eq!(store.loaded_levels(), 2);
+
+        // B comes back after its eviction, and A goes
+        let level = store.get(B).unwrap().unwrap();
+        assert!(level.layer_instances.is_some());
+        assert_eq!(loaded(&store), [false, true, true]);
+
+        // Lowering the limit evicts right away
+        let store = store.with_max_levels(1);
+        assert_eq!(loaded(&store), [false, true, false]);
+    }
+
+    #[test]
+    fn evicts_by_size() {
+        // Room for B and C (2013 bytes each), not for A (2298 bytes) too
+        let mut store = store().with_max_bytes(4500);
+        store.get(B).unwrap();
+        store.get(C).unwrap();
+        assert_eq!(loaded(&store), [false, true, true]);
+        assert_eq!(store.loaded_bytes(), 4026);
+        store.get(A).unwrap();
+        assert_eq!(loaded(&store), [true, false, true]);
+        assert_eq!(store.loaded_bytes(), 4311);
+
+        // The most recently used level is kept, even over the budget
+        let mut store = store.with_max_bytes(0);
+        assert_eq!(loaded(&store), [true, false, false]);
+        store.get(C).unwrap();
+        assert_eq!(loaded(&store), [false, false, true]);
+        assert_eq!(store.loaded_levels(), 1);
+    }
+
+    #[test]
+    fn unload() {
+        let mut store = store();
+        store.get(A).unwrap();
+        store.get(C).unwrap();
+        assert!(store.unload(A));
+        assert!(!store.unload(A));
+        assert!(!store.unload(B));
+        assert!(!store.unload(42));
+        assert_eq!(loaded(&store), [false, false, true]);
+        assert_eq!(store.loaded_bytes(), 2013);
+
+        store.get(A).unwrap();
+        store.unload_all();
+        assert_eq!(loaded(&store), [false; 3]);
+        assert_eq!((store.loaded_levels(), store.loaded_bytes()), (0, 0));
+    }
+
+    #[test]
+    fn levels_in_the_project_file() {
+        let source: MemorySource = [(
+            "game.ldtk",
+            include_bytes!("../assets/game_1-1-3.ldtk").to_vec(),
+        )]
+        .into_iter()
+        .collect();
+        let mut store = LevelStore::from_source(source, "game.ldtk").unwrap();
+        let uid = store.project().levels[0].uid;
+        assert!(store.is_loaded(uid));
+        assert!(store.get(uid).unwrap().is_some());
+        assert_eq!(store.loaded_levels(), 0);
+        assert!(!store.unload(uid));
+    }
+}
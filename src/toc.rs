@@ -0,0 +1,351 @@
+// Typed access to the table of contents: the entities whose definition is
+// marked "export to table of contents", listed in the project file with
+// their position, size and the values of their exported fields. A
+// TableOfContents reads just that part of the project file, so a world map
+// or a level select screen can be drawn without opening any level.
+
+use crate::{
+    AssetSource, EntityDefinition, EntityRef, FieldAccess, FieldInstance, LdtkError,
+    LdtkTableOfContentEntry, LdtkTocInstanceData, Project, Rect, asset, parse_project, upgrade,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::Path;
+
+/// An entity listed in the table of contents.
+///
+/// ```ignore
+/// for checkpoint in project.toc_entries("Checkpoint") {
+///     let name = checkpoint.get_string("name")?;
+///     println!("{name} at {}, {}", checkpoint.world_x, checkpoint.world_y);
+/// }
+/// ```
+///
+/// The exported fields are read through [`FieldAccess`], typed with the
+/// entity's field definitions. Fields that don't have a definition are
+/// left out, `fields` still holds them as LDtk wrote them.
+#[derive(Debug, Clone)]
+pub struct TocEntry<'a> {
+    /// The identifier of the entity.
+    pub identifier: &'a str,
+    /// The iids of the entity and of the layer, level and world it is in,
+    /// see [`Project::resolve`] to get to the entity itself.
+    pub iids: &'a EntityRef,
+    /// The position of the entity's pivot in world pixels.
+    pub world_x: i64,
+    pub world_y: i64,
+    pub width: i64,
+    pub height: i64,
+    /// The exported fields as raw JSON, keyed by field identifier.
+    pub fields: Option<&'a Value>,
+    /// The definition of the entity, if the project still has one.
+    pub definition: Option<&'a EntityDefinition>,
+    field_instances: Vec<FieldInstance>,
+}
+
+impl<'a> TocEntry<'a> {
+    fn new(
+        identifier: &'a str,
+        data: &'a LdtkTocInstanceData,
+        definition: Option<&'a EntityDefinition>,
+    ) -> Self {
+        let values = data.fields.as_ref().and_then(Value::as_object);
+        let field_instances = definition
+            .zip(values)
+            .map(|(def, values)| {
+                def.field_defs
+                    .iter()
+                    .filter_map(|f| {
+                        let value = values.get(&f.identifier)?;
+                        Some(FieldInstance {
+                            identifier: f.identifier.clone(),
+                            tile: None,
+                            field_instance_type: f.field_definition_type.clone(),
+                            value: Some(value.clone()),
+                            def_uid: f.uid,
+                            real_editor_values: Vec::new(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        TocEntry {
+            identifier,
+            iids: &data.iids,
+            world_x: data.world_x,
+            world_y: data.world_y,
+            width: data.wid_px,
+            height: data.hei_px,
+            fields: data.fields.as_ref(),
+            definition,
+            field_instances,
+        }
+    }
+
+    /// The entity's bounding box in world pixels, placed around the
+    /// position with the pivot of its definition.
+    pub fn bounds(&self) -> Rect {
+        let (pivot_x, pivot_y) = self
+            .definition
+            .map_or((0.0, 0.0), |d| (d.pivot_x, d.pivot_y));
+        let left = self.world_x - (pivot_x * self.width as f64).round() as i64;
+        let top = self.world_y - (pivot_y * self.height as f64).round() as i64;
+        Rect::new(left, top, self.width, self.height)
+    }
+}
+
+impl FieldAccess for TocEntry<'_> {
+    fn field_instances(&self) -> &[FieldInstance] {
+        &self.field_instances
+    }
+}
+
+/// The table of contents of a project, read from the project file without
+/// its levels.
+///
+/// ```ignore
+/// let toc = TableOfContents::try_new("assets/game.ldtk")?;
+/// for checkpoint in toc.toc_entries("Checkpoint") {
+///     draw_marker(checkpoint.bounds());
+/// }
+/// ```
+///
+/// Only the table of contents and the entity definitions (to type the
+/// fields) are kept. Files saved by older versions of LDtk are read in
+/// full to be upgraded, the ones written before LDtk had a table of
+/// contents give an empty one.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TableOfContents {
+    /// Same as `Project::toc`.
+    #[serde(default)]
+    pub toc: Vec<LdtkTableOfContentEntry>,
+    /// Same as `Project::defs.entities`.
+    #[serde(rename = "defs", deserialize_with = "entity_defs")]
+    pub entity_defs: Vec<EntityDefinition>,
+}
+
+// Pick the entity definitions out of `defs`, skipping the rest.
+fn entity_defs<'de, D: serde::Deserializer<'de>>(d: D) -> Result<Vec<EntityDefinition>, D::Error> {
+    #[derive(Deserialize)]
+    struct Defs {
+        entities: Vec<EntityDefinition>,
+    }
+    Defs::deserialize(d).map(|defs| defs.entities)
+}
+
+impl TableOfContents {
+    pub fn new<P: AsRef<Path>>(f: P) -> Self {
+        TableOfContents::try_new(f).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    // Read the table of contents of a project file on disk.
+    pub fn try_new<P: AsRef<Path>>(f: P) -> Result<Self, LdtkError> {
+        let path = f.as_ref();
+        let b = std::fs::read(path).map_err(|e| LdtkError::from_io(e, Some(path.to_path_buf())))?;
+        parse_toc(&b).map_err(|e| LdtkError::from_json(e, Some(path.to_path_buf())))
+    }
+
+    // Same as try_new(), reading the project file from an AssetSource.
+    pub fn try_from_source<S: AssetSource + ?Sized>(
+        source: &S,
+        f: &str,
+    ) -> Result<Self, LdtkError> {
+        let b = asset::read(source, f)?;
+        parse_toc(&b).map_err(|e| LdtkError::from_json(e, Some(source.location(f))))
+    }
+
+    pub fn try_from_slice(b: &[u8]) -> Result<Self, LdtkError> {
+        parse_toc(b).map_err(|e| LdtkError::from_json(e, None))
+    }
+
+    /// The entities with this identifier, see [`Project::toc_entries`].
+    pub fn toc_entries(&self, identifier: &str) -> Vec<TocEntry<'_>> {
+        entries(&self.toc, &self.entity_defs, identifier)
+    }
+
+    /// The entry of the entity a reference points to.
+    pub fn resolve(&self, r: &EntityRef) -> Option<TocEntry<'_>> {
+        resolve(&self.toc, &self.entity_defs, r)
+    }
+}
+
+impl Project {
+    /// The entities with this identifier listed in the table of contents.
+    /// Only entities whose definition is exported to the table of
+    /// contents are there, but they are there whether their level is
+    /// loaded or not.
+    pub fn toc_entries(&self, identifier: &str) -> Vec<TocEntry<'_>> {
+        entries(&self.toc, &self.defs.entities, identifier)
+    }
+
+    /// The table of contents entry of the entity a reference points to,
+    /// which unlike [`Project::resolve`] doesn't need its level loaded.
+    pub fn resolve_toc(&self, r: &EntityRef) -> Option<TocEntry<'_>> {
+        resolve(&self.toc, &self.defs.entities, r)
+    }
+}
+
+fn entries<'a>(
+    toc: &'a [LdtkTableOfContentEntry],
+    defs: &'a [EntityDefinition],
+    identifier: &str,
+) -> Vec<TocEntry<'a>> {
+    let definition = defs.iter().find(|d| d.identifier == identifier);
+    toc.iter()
+        .filter(|e| e.identifier == identifier)
+        .flat_map(|e| {
+            e.instances_data
+                .iter()
+                .map(move |data| TocEntry::new(&e.identifier, data, definition))
+        })
+        .collect()
+}
+
+fn resolve<'a>(
+    toc: &'a [LdtkTableOfContentEntry],
+    defs: &'a [EntityDefinition],
+    r: &EntityRef,
+) -> Option<TocEntry<'a>> {
+    toc.iter().find_map(|e| {
+        let data = e
+            .instances_data
+            .iter()
+            .find(|d| d.iids.entity_iid == r.entity_iid)?;
+        let definition = defs.iter().find(|d| d.identifier == e.identifier);
+        Some(TocEntry::new(&e.identifier, data, definition))
+    })
+}
+
+fn parse_toc(b: &[u8]) -> Result<TableOfContents, serde_json::Error> {
+    match upgrade::SchemaVersion::detect(b) {
+        Some(v) if v != upgrade::SchemaVersion::CURRENT => {
+            let project = parse_project(b)?;
+            Ok(TableOfContents {
+                toc: project.toc,
+                entity_defs: project.defs.entities,
+            })
+        }
+        _ => serde_json::from_slice(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+    use serde_json::json;
+
+    // game_1-1-3.ldtk saved as the current version, with its birds
+    // exported to the table of contents. Bird_0 sits at a made up position
+    // and the last one has a field without definition.
+    fn project_file() -> Vec<u8> {
+        let project = Project::new("assets/game_1-1-3.ldtk");
+        let layer = &project.levels[0].layer_instances.as_ref().unwrap()[0];
+        let world_iid = project.iter_worlds().next().unwrap().iid.to_string();
+        let birds: Vec<_> = layer
+            .entity_instances
+            .iter()
+            .filter(|e| e.identifier == "Bird")
+            .enumerate()
+            .map(|(i, bird)| {
+                let mut fields = json!({ "color": bird.get_color("color").unwrap().to_hex() });
+                if i > 0 {
+                    fields["extra"] = json!(i);
+                }
+                json!({
+                    "iids": {
+                        "entityIid": bird.iid,
+                        "layerIid": layer.iid,
+                        "levelIid": project.levels[0].iid,
+                        "worldIid": world_iid,
+                    },
+                    "worldX": 100 * i,
+                    "worldY": 50,
+                    "widPx": bird.width,
+                    "heiPx": bird.height,
+                    "fields": fields,
+                })
+            })
+            .collect();
+        assert!(birds.len() > 1);
+
+        let mut json = serde_json::to_value(&project).unwrap();
+        json["jsonVersion"] = "1.5.3".into();
+        json["toc"] = json!([{ "identifier": "Bird", "instancesData": birds }]);
+        let bird_def = json["defs"]["entities"]
+            .as_array_mut()
+            .unwrap()
+            .iter_mut()
+            .find(|d| d["identifier"] == "Bird")
+            .unwrap();
+        bird_def["pivotX"] = 0.5.into();
+        bird_def["pivotY"] = 1.0.into();
+        serde_json::to_vec(&json).unwrap()
+    }
+
+    #[test]
+    fn reads_only_the_toc() {
+        let b = project_file();
+        let toc = TableOfContents::try_from_slice(&b).unwrap();
+        assert_eq!(toc.toc.len(), 1);
+        let project = Project::from_slice(&b);
+        assert_eq!(toc.entity_defs.len(), project.defs.entities.len());
+
+        let birds = toc.toc_entries("Bird");
+        assert_eq!(birds.len(), toc.toc[0].instances_data.len());
+        assert_eq!(
+            birds.iter().map(|b| &b.iids.entity_iid).collect::<Vec<_>>(),
+            project
+                .toc_entries("Bird")
+                .iter()
+                .map(|b| &b.iids.entity_iid)
+                .collect::<Vec<_>>()
+        );
+        assert!(toc.toc_entries("Hero").is_empty());
+
+        let bird = &birds[0];
+        assert_eq!(bird.identifier, "Bird");
+        assert_eq!(
+            bird.get_color("color").unwrap(),
+            Color::rgb(0xea, 0x0c, 0xcc)
+        );
+        // The bird stands on its position
+        assert_eq!(
+            bird.bounds(),
+            Rect::new(-bird.width / 2, 50 - bird.height, bird.width, bird.height)
+        );
+        // Fields without definition are only in the raw JSON
+        assert_eq!(birds[1].fields.unwrap()["extra"], 1);
+        assert!(birds[1].get_int("extra").is_err());
+    }
+
+    #[test]
+    fn resolve() {
+        let b = project_file();
+        let toc = TableOfContents::try_from_slice(&b).unwrap();
+        let project = Project::from_slice(&b);
+        let r = &toc.toc[0].instances_data[1].iids;
+
+        let entry = toc.resolve(r).unwrap();
+        assert_eq!((entry.world_x, entry.world_y), (100, 50));
+        assert!(entry.definition.is_some());
+        // The same entity, through its level
+        let location = project.resolve(r).unwrap();
+        assert_eq!(location.entity.iid, r.entity_iid);
+        assert_eq!(project.resolve_toc(r).unwrap().world_x, 100);
+
+        let dangling = EntityRef {
+            entity_iid: "nope".to_string(),
+            ..r.clone()
+        };
+        assert!(toc.resolve(&dangling).is_none());
+    }
+
+    #[test]
+    fn old_files_have_an_empty_toc() {
+        let toc = TableOfContents::new("assets/game_1-1-3.ldtk");
+        assert!(toc.toc.is_empty());
+        assert!(toc.toc_entries("Bird").is_empty());
+        assert!(!toc.entity_defs.is_empty());
+    }
+}
@@ -0,0 +1,997 @@
+// Support for files saved by older versions of LDtk.
+//
+// Every schema we know about has its own generated module (json_0_6_3.rs
+// and so on). An old file is first deserialized with the model matching
+// its `jsonVersion`, which checks it exactly the way that version of LDtk
+// wrote it. The result is turned back into JSON and brought forward one
+// schema at a time by the steps at the bottom of this file, and the final
+// JSON is deserialized into the current Project/Level structs.
+//
+// Levels are upgraded separately from the rest of the project because
+// external level files are loaded after the project itself. The level
+// steps get the (already upgraded) project definitions so they can fill
+// in values LDtk copies from the definitions, like entity sizes and tags.
+
+use crate::{
+    Definitions, EntityDefinition, Level, Project, WorldLayout, json_0_6_3, json_0_6_4, json_0_7_0,
+    json_0_8_1, json_0_9_2, json_0_9_3, json_0_10_beta1, json_0_10_beta2, json_1_0_0, json_1_1_0,
+    json_1_1_3,
+};
+use serde_json::{Map, Value, json};
+
+type Object = Map<String, Value>;
+
+/// The LDtk JSON schemas this crate has a generated model for, oldest
+/// first. Each one is used for files saved by that version of LDtk up to
+/// (but not including) the next one in the list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SchemaVersion {
+    V0_6_3,
+    V0_6_4,
+    V0_7_0,
+    V0_8_1,
+    V0_9_2,
+    V0_9_3,
+    V0_10_0Beta1,
+    V0_10_0Beta2,
+    V1_0_0,
+    V1_1_0,
+    V1_1_3,
+    V1_5_3,
+}
+
+use SchemaVersion::*;
+
+const ALL_VERSIONS: [SchemaVersion; 12] = [
+    V0_6_3,
+    V0_6_4,
+    V0_7_0,
+    V0_8_1,
+    V0_9_2,
+    V0_9_3,
+    V0_10_0Beta1,
+    V0_10_0Beta2,
+    V1_0_0,
+    V1_1_0,
+    V1_1_3,
+    V1_5_3,
+];
+
+// sorts after any pre-release of the same version number
+const RELEASE: u32 = u32::MAX;
+
+impl SchemaVersion {
+    /// The schema of the structs at the root of this crate.
+    pub const CURRENT: SchemaVersion = V1_5_3;
+
+    /// The LDtk version the model was generated from, like "0.9.3".
+    pub fn as_str(self) -> &'static str {
+        match self {
+            V0_6_3 => "0.6.3",
+            V0_6_4 => "0.6.4",
+            V0_7_0 => "0.7.0",
+            V0_8_1 => "0.8.1",
+            V0_9_2 => "0.9.2",
+            V0_9_3 => "0.9.3",
+            V0_10_0Beta1 => "0.10.0-beta1",
+            V0_10_0Beta2 => "0.10.0-beta2",
+            V1_0_0 => "1.0.0",
+            V1_1_0 => "1.1.0",
+            V1_1_3 => "1.1.3",
+            V1_5_3 => "1.5.3",
+        }
+    }
+
+    // (major, minor, patch, pre-release) of the LDtk release the model
+    // was generated from.
+    fn key(self) -> (u32, u32, u32, u32) {
+        match self {
+            V0_6_3 => (0, 6, 3, RELEASE),
+            V0_6_4 => (0, 6, 4, RELEASE),
+            V0_7_0 => (0, 7, 0, RELEASE),
+            V0_8_1 => (0, 8, 1, RELEASE),
+            V0_9_2 => (0, 9, 2, RELEASE),
+            V0_9_3 => (0, 9, 3, RELEASE),
+            V0_10_0Beta1 => (0, 10, 0, 1),
+            V0_10_0Beta2 => (0, 10, 0, 2),
+            V1_0_0 => (1, 0, 0, RELEASE),
+            V1_1_0 => (1, 1, 0, RELEASE),
+            V1_1_3 => (1, 1, 3, RELEASE),
+            V1_5_3 => (1, 5, 3, RELEASE),
+        }
+    }
+
+    /// Pick the model to read a file with: the newest one that isn't newer
+    /// than the file itself. Files from before 0.6.3 get the oldest model,
+    /// and anything that doesn't look like a version number gets the
+    /// current one.
+    pub fn from_json_version(v: &str) -> SchemaVersion {
+        let Some(key) = version_key(v) else {
+            return SchemaVersion::CURRENT;
+        };
+        ALL_VERSIONS
+            .iter()
+            .rev()
+            .find(|s| s.key() <= key)
+            .copied()
+            .unwrap_or(V0_6_3)
+    }
+
+    // Version of a project file (`jsonVersion`) or of an external level
+    // file (`__header__.appVersion`), if the file has one. The file is
+    // never parsed as a whole, a key LDtk wouldn't write (with escapes in
+    // it, say) isn't found.
+    pub(crate) fn detect(b: &[u8]) -> Option<SchemaVersion> {
+        peek_string(b, "jsonVersion")
+            .or_else(|| peek_string(b, "appVersion"))
+            .map(|v| SchemaVersion::from_json_version(&v))
+    }
+}
+
+impl std::fmt::Display for SchemaVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// Find `"key": "value"` without parsing the whole file. LDtk writes the
+// version near the top. The key can also show up as a string value (an
+// identifier named like it, say), so matches that aren't followed by a
+// colon and a string are skipped.
+fn peek_string(b: &[u8], key: &str) -> Option<String> {
+    let needle = format!("\"{key}\"");
+    let mut from = 0;
+    while let Some(i) = b[from..]
+        .windows(needle.len())
+        .position(|w| w == needle.as_bytes())
+    {
+        let end = from + i + needle.len();
+        from = end;
+        let Some(rest) = b[end..].trim_ascii_start().strip_prefix(b":") else {
+            continue;
+        };
+        if let Some(Ok(value)) = serde_json::Deserializer::from_slice(rest)
+            .into_iter::<String>()
+            .next()
+        {
+            return Some(value);
+        }
+    }
+    None
+}
+
+// "1.5.3" or "0.10.0-beta1" to a sortable tuple
+fn version_key(v: &str) -> Option<(u32, u32, u32, u32)> {
+    let (numbers, pre) = match v.trim().split_once('-') {
+        Some((n, p)) => (n, Some(p)),
+        None => (v.trim(), None),
+    };
+    let mut parts = numbers.split('.').map(str::parse::<u32>);
+    let major = parts.next()?.ok()?;
+    let minor = parts.next()?.ok()?;
+    let patch = parts.next().transpose().ok()?.unwrap_or(0);
+    let pre = match pre {
+        None => RELEASE,
+        Some(p) => p
+            .trim_start_matches(|c: char| c.is_alphabetic())
+            .parse()
+            .unwrap_or(0),
+    };
+    Some((major, minor, patch, pre))
+}
+
+// Deserialize with the historical model for `$version`, then turn the
+// result back into JSON.
+macro_rules! reserialize {
+    ($version:expr, $b:expr, $model:ident) => {
+        match $version {
+            V0_6_3 => to_json::<json_0_6_3::$model>($b),
+            V0_6_4 => to_json::<json_0_6_4::$model>($b),
+            V0_7_0 => to_json::<json_0_7_0::$model>($b),
+            V0_8_1 => to_json::<json_0_8_1::$model>($b),
+            V0_9_2 => to_json::<json_0_9_2::$model>($b),
+            V0_9_3 => to_json::<json_0_9_3::$model>($b),
+            V0_10_0Beta1 => to_json::<json_0_10_beta1::$model>($b),
+            V0_10_0Beta2 => to_json::<json_0_10_beta2::$model>($b),
+            V1_0_0 => to_json::<json_1_0_0::$model>($b),
+            V1_1_0 => to_json::<json_1_1_0::$model>($b),
+            V1_1_3 => to_json::<json_1_1_3::$model>($b),
+            V1_5_3 => to_json::<$model>($b),
+        }
+    };
+}
+
+fn to_json<T: serde::de::DeserializeOwned + serde::Serialize>(
+    b: &[u8],
+) -> Result<Value, serde_json::Error> {
+    serde_json::to_value(serde_json::from_slice::<T>(b)?)
+}
+
+// What the level steps may look at besides the level itself.
+pub(crate) struct LevelContext<'a> {
+    pub(crate) defs: Option<&'a Definitions>,
+    pub(crate) world_layout: Option<&'a WorldLayout>,
+}
+
+impl LevelContext<'_> {
+    fn entity_def(&self, uid: i64) -> Option<&EntityDefinition> {
+        self.defs?.entities.iter().find(|d| d.uid == uid)
+    }
+}
+
+// Read a project file written with the given schema and upgrade it,
+// including any levels embedded in it.
+pub(crate) fn project(b: &[u8], version: SchemaVersion) -> Result<Project, serde_json::Error> {
+    let mut value = reserialize!(version, b, Project)?;
+    let Some(p) = value.as_object_mut() else {
+        return serde_json::from_value(value);
+    };
+
+    // levels are upgraded once the definitions are
+    let levels = take_array(p, "levels");
+    let world_levels: Vec<Vec<Value>> = objects(p, "worlds")
+        .map(|w| take_array(w, "levels"))
+        .collect();
+
+    for step in steps_after(version) {
+        (step.project)(p);
+    }
+    let mut project: Project = serde_json::from_value(value)?;
+
+    let ctx = LevelContext {
+        defs: Some(&project.defs),
+        world_layout: project.world_layout.as_ref(),
+    };
+    project.levels = levels
+        .into_iter()
+        .map(|l| level_from_value(l, version, &ctx))
+        .collect::<Result<_, _>>()?;
+    for (world, levels) in project.worlds.iter_mut().zip(world_levels) {
+        let ctx = LevelContext {
+            defs: Some(&project.defs),
+            world_layout: world.world_layout.as_ref(),
+        };
+        world.levels = levels
+            .into_iter()
+            .map(|l| level_from_value(l, version, &ctx))
+            .collect::<Result<_, _>>()?;
+    }
+    Ok(project)
+}
+
+// Read a level (usually an external .ldtkl file) written with the given
+// schema and upgrade it.
+pub(crate) fn level(
+    b: &[u8],
+    version: SchemaVersion,
+    ctx: &LevelContext,
+) -> Result<Level, serde_json::Error> {
+    let value = reserialize!(version, b, Level)?;
+    level_from_value(value, version, ctx)
+}
+
+fn level_from_value(
+    mut value: Value,
+    version: SchemaVersion,
+    ctx: &LevelContext,
+) -> Result<Level, serde_json::Error> {
+    if let Some(l) = value.as_object_mut() {
+        for step in steps_after(version) {
+            (step.level)(l, ctx);
+        }
+    }
+    serde_json::from_value(value)
+}
+
+//
+// Helpers for poking at the JSON
+//
+
+// Set a field that is missing or null.
+fn fill(o: &mut Object, key: &str, v: Value) {
+    if o.get(key).is_none_or(Value::is_null) {
+        o.insert(key.to_string(), v);
+    }
+}
+
+// All the objects in the array at `key`.
+fn objects<'a>(o: &'a mut Object, key: &str) -> impl Iterator<Item = &'a mut Object> {
+    o.get_mut(key)
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_object_mut)
+}
+
+fn take_array(o: &mut Object, key: &str) -> Vec<Value> {
+    match o.get_mut(key).map(Value::take) {
+        Some(Value::Array(a)) => {
+            o.insert(key.to_string(), json!([]));
+            a
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn int(o: &Object, key: &str) -> Option<i64> {
+    o.get(key).and_then(Value::as_i64)
+}
+
+fn defs(p: &mut Object) -> Option<&mut Object> {
+    p.get_mut("defs").and_then(Value::as_object_mut)
+}
+
+// Run `f` on every object in `defs[key]`.
+fn each_def(p: &mut Object, key: &str, mut f: impl FnMut(&mut Object)) {
+    if let Some(d) = defs(p) {
+        objects(d, key).for_each(&mut f);
+    }
+}
+
+// Field definitions live on entities and on levels.
+fn each_field_def(p: &mut Object, mut f: impl FnMut(&mut Object)) {
+    each_def(p, "entities", |e| objects(e, "fieldDefs").for_each(&mut f));
+    each_def(p, "levelFields", &mut f);
+}
+
+fn each_enum_def(p: &mut Object, mut f: impl FnMut(&mut Object)) {
+    each_def(p, "enums", &mut f);
+    each_def(p, "externalEnums", &mut f);
+}
+
+// Run `f` on every layer instance of a level along with the level uid.
+fn each_layer(l: &mut Object, mut f: impl FnMut(&mut Object, i64)) {
+    let level_uid = int(l, "uid").unwrap_or(0);
+    objects(l, "layerInstances").for_each(|li| f(li, level_uid));
+}
+
+// Layouts where LDtk places levels itself and doesn't export world
+// coordinates for entities.
+fn is_linear(layout: Option<&WorldLayout>) -> bool {
+    matches!(
+        layout,
+        Some(WorldLayout::LinearHorizontal | WorldLayout::LinearVertical)
+    )
+}
+
+// Files from before 0.10 have no iids, so we make up stable ones from the
+// uids that they do have.
+fn legacy_level_iid(level_uid: i64) -> String {
+    format!("legacy-level-{level_uid}")
+}
+
+fn legacy_layer_iid(level_uid: i64, layer_def_uid: i64) -> String {
+    format!("legacy-layer-{level_uid}-{layer_def_uid}")
+}
+
+fn legacy_entity_iid(level_uid: i64, layer_def_uid: i64, index: usize) -> String {
+    format!("legacy-entity-{level_uid}-{layer_def_uid}-{index}")
+}
+
+const LEGACY_PROJECT_IID: &str = "legacy-project";
+const LEGACY_WORLD_IID: &str = "legacy-world";
+
+// Tileset geometry needed to turn a tile id into a rectangle.
+struct TilesetGrid {
+    uid: i64,
+    c_wid: i64,
+    grid: i64,
+    spacing: i64,
+    padding: i64,
+}
+
+impl TilesetGrid {
+    fn all(p: &mut Object) -> Vec<TilesetGrid> {
+        let mut out = Vec::new();
+        each_def(p, "tilesets", |t| {
+            out.push(TilesetGrid {
+                uid: int(t, "uid").unwrap_or(0),
+                c_wid: int(t, "__cWid").unwrap_or(0),
+                grid: int(t, "tileGridSize").unwrap_or(0),
+                spacing: int(t, "spacing").unwrap_or(0),
+                padding: int(t, "padding").unwrap_or(0),
+            })
+        });
+        out
+    }
+
+    fn rect(&self, tile_id: i64) -> Value {
+        let (cx, cy) = match self.c_wid {
+            0 => (0, 0),
+            w => (tile_id % w, tile_id / w),
+        };
+        json!({
+            "tilesetUid": self.uid,
+            "x": self.padding + cx * (self.grid + self.spacing),
+            "y": self.padding + cy * (self.grid + self.spacing),
+            "w": self.grid,
+            "h": self.grid,
+        })
+    }
+}
+
+// [x, y, w, h] plus a tileset uid to a TilesetRectangle
+fn rect_from_src(tileset_uid: i64, src: &Value) -> Option<Value> {
+    let src = src.as_array()?;
+    let n = |i: usize| src.get(i).and_then(Value::as_i64);
+    Some(json!({
+        "tilesetUid": tileset_uid,
+        "x": n(0)?,
+        "y": n(1)?,
+        "w": n(2)?,
+        "h": n(3)?,
+    }))
+}
+
+//
+// The upgrade steps. Each one takes JSON matching the previous schema and
+// adds or converts whatever the `to` schema introduced, using the defaults
+// LDtk itself applies when it opens an older project.
+//
+
+struct Step {
+    to: SchemaVersion,
+    project: fn(&mut Object),
+    level: fn(&mut Object, &LevelContext),
+}
+
+const STEPS: [Step; 11] = [
+    Step {
+        to: V0_6_4,
+        project: project_0_6_4,
+        level: level_0_6_4,
+    },
+    Step {
+        to: V0_7_0,
+        project: project_0_7_0,
+        level: no_level_changes,
+    },
+    Step {
+        to: V0_8_1,
+        project: project_0_8_1,
+        level: level_0_8_1,
+    },
+    Step {
+        to: V0_9_2,
+        project: project_0_9_2,
+        level: level_0_9_2,
+    },
+    Step {
+        to: V0_9_3,
+        project: project_0_9_3,
+        level: no_level_changes,
+    },
+    Step {
+        to: V0_10_0Beta1,
+        project: project_0_10_0_beta1,
+        level: level_0_10_0_beta1,
+    },
+    Step {
+        to: V0_10_0Beta2,
+        project: project_0_10_0_beta2,
+        level: level_0_10_0_beta2,
+    },
+    Step {
+        to: V1_0_0,
+        project: project_1_0_0,
+        level: no_level_changes,
+    },
+    Step {
+        to: V1_1_0,
+        project: project_1_1_0,
+        level: no_level_changes,
+    },
+    Step {
+        to: V1_1_3,
+        project: no_project_changes,
+        level: no_level_changes,
+    },
+    Step {
+        to: V1_5_3,
+        project: project_1_5_3,
+        level: level_1_5_3,
+    },
+];
+
+fn steps_after(version: SchemaVersion) -> impl Iterator<Item = &'static Step> {
+    STEPS.iter().filter(move |s| s.to > version)
+}
+
+fn no_project_changes(_: &mut Object) {}
+
+fn no_level_changes(_: &mut Object, _: &LevelContext) {}
+
+fn project_0_6_4(p: &mut Object) {
+    fill(p, "exportPng", json!(false));
+}
+
+fn level_0_6_4(l: &mut Object, ctx: &LevelContext) {
+    fill(l, "bgPivotX", json!(0.5));
+    fill(l, "bgPivotY", json!(0.5));
+    each_layer(l, |li, _| {
+        for e in objects(li, "entityInstances") {
+            let def = int(e, "defUid").and_then(|uid| ctx.entity_def(uid));
+            let pivot = def.map_or([0.0, 0.0], |d| [d.pivot_x, d.pivot_y]);
+            fill(e, "__pivot", json!(pivot));
+        }
+    });
+}
+
+fn project_0_7_0(p: &mut Object) {
+    fill(p, "backupLimit", json!(10));
+    fill(p, "backupOnSave", json!(false));
+}
+
+fn project_0_8_1(p: &mut Object) {
+    fill(
+        p,
+        "defs",
+        json!({
+            "entities": [],
+            "enums": [],
+            "externalEnums": [],
+            "layers": [],
+            "levelFields": [],
+            "tilesets": [],
+        }),
+    );
+    fill(p, "defaultLevelWidth", json!(256));
+    fill(p, "defaultLevelHeight", json!(256));
+    fill(p, "flags", json!([]));
+    fill(p, "worldLayout", json!("Free"));
+    if let Some(d) = defs(p) {
+        fill(d, "levelFields", json!([]));
+    }
+
+    each_def(p, "entities", |e| {
+        // maxPerLevel was replaced by maxCount + limitScope
+        let max_count = e.remove("maxPerLevel").unwrap_or(json!(0));
+        fill(e, "maxCount", max_count);
+        fill(e, "limitScope", json!("PerLevel"));
+        fill(e, "limitBehavior", json!("MoveLastOne"));
+        fill(e, "renderMode", json!("Rectangle"));
+        if e.get("tileRenderMode").and_then(Value::as_str) == Some("Crop") {
+            e.insert("tileRenderMode".into(), json!("Cover"));
+        }
+        fill(e, "tileRenderMode", json!("FitInside"));
+        fill(e, "fillOpacity", json!(1.0));
+        fill(e, "lineOpacity", json!(1.0));
+        fill(e, "hollow", json!(false));
+        fill(e, "keepAspectRatio", json!(false));
+        fill(e, "resizableX", json!(false));
+        fill(e, "resizableY", json!(false));
+        fill(e, "tags", json!([]));
+    });
+    each_field_def(p, |f| {
+        fill(f, "editorCutLongValues", json!(true));
+        fill(f, "editorDisplayMode", json!("ValueOnly"));
+        fill(f, "editorDisplayPos", json!("Above"));
+    });
+    each_def(p, "layers", |ld| {
+        if let Some(t) = ld.get("__type").cloned() {
+            fill(ld, "type", t);
+        }
+        fill(ld, "excludedTags", json!([]));
+        fill(ld, "requiredTags", json!([]));
+        // IntGrid values used to be identified by their index, they now
+        // have an explicit value starting at 1
+        for (i, v) in objects(ld, "intGridValues").enumerate() {
+            fill(v, "value", json!(i + 1));
+        }
+    });
+}
+
+fn level_0_8_1(l: &mut Object, ctx: &LevelContext) {
+    fill(l, "fieldInstances", json!([]));
+    each_layer(l, |li, _| {
+        fill(li, "visible", json!(true));
+
+        // the sparse intGrid list became a dense CSV array where 0 means
+        // empty, so the old 0-based values are shifted by one
+        if li.get("intGridCsv").is_none_or(Value::is_null) {
+            let len = int(li, "__cWid").unwrap_or(0) * int(li, "__cHei").unwrap_or(0);
+            let mut csv = vec![0; len.max(0) as usize];
+            for cell in objects(li, "intGrid") {
+                if let (Some(coord), Some(v)) = (int(cell, "coordId"), int(cell, "v"))
+                    && let Some(c) = csv.get_mut(coord as usize)
+                {
+                    *c = v + 1;
+                }
+            }
+            li.insert("intGridCsv".into(), json!(csv));
+        }
+
+        // entities weren't resizable yet, so they have their definition's size
+        let grid_size = int(li, "__gridSize").unwrap_or(0);
+        for e in objects(li, "entityInstances") {
+            let def = int(e, "defUid").and_then(|uid| ctx.entity_def(uid));
+            let (w, h) = def.map_or((grid_size, grid_size), |d| (d.width, d.height));
+            fill(e, "width", json!(w));
+            fill(e, "height", json!(h));
+        }
+    });
+}
+
+fn project_0_9_2(p: &mut Object) {
+    fill(p, "levelNamePattern", json!("Level_%idx"));
+    each_def(p, "layers", |ld| {
+        for g in objects(ld, "autoRuleGroups") {
+            fill(g, "isOptional", json!(false));
+        }
+    });
+    each_enum_def(p, |e| {
+        for v in objects(e, "values") {
+            fill(v, "color", json!(0));
+        }
+    });
+    each_def(p, "tilesets", |t| {
+        let px_wid = int(t, "pxWid").unwrap_or(0);
+        let px_hei = int(t, "pxHei").unwrap_or(0);
+        let grid = int(t, "tileGridSize").unwrap_or(0);
+        let spacing = int(t, "spacing").unwrap_or(0);
+        let padding = int(t, "padding").unwrap_or(0);
+        let cells = |px: i64| match grid + spacing {
+            0 => 0,
+            step => (px - padding * 2 + step - 1) / step,
+        };
+        fill(t, "__cWid", json!(cells(px_wid)));
+        fill(t, "__cHei", json!(cells(px_hei)));
+        fill(t, "customData", json!([]));
+        fill(t, "enumTags", json!([]));
+    });
+}
+
+fn level_0_9_2(l: &mut Object, _: &LevelContext) {
+    fill(l, "useAutoIdentifier", json!(false));
+    each_layer(l, |li, _| fill(li, "optionalRules", json!([])));
+}
+
+fn project_0_9_3(p: &mut Object) {
+    // exportPng was replaced by imageExportMode
+    let export_png = p.get("exportPng").and_then(Value::as_bool) == Some(true);
+    let mode = if export_png {
+        "OneImagePerLayer"
+    } else {
+        "None"
+    };
+    fill(p, "imageExportMode", json!(mode));
+    each_field_def(p, |f| {
+        // typo fix in the field name
+        if let Some(mode) = f.remove("textLangageMode") {
+            fill(f, "textLanguageMode", mode);
+        }
+    });
+}
+
+fn project_0_10_0_beta1(p: &mut Object) {
+    fill(p, "appBuildId", json!(0.0));
+    fill(p, "identifierStyle", json!("Capitalize"));
+    fill(p, "worlds", json!([]));
+
+    // entity tiles went from a tile id to a rectangle
+    let tilesets = TilesetGrid::all(p);
+    each_def(p, "entities", |e| {
+        fill(e, "tileOpacity", json!(1.0));
+        if let (Some(ts), Some(t)) = (int(e, "tilesetId"), int(e, "tileId"))
+            && let Some(grid) = tilesets.iter().find(|g| g.uid == ts)
+        {
+            fill(e, "tileRect", grid.rect(t));
+        }
+    });
+    each_enum_def(p, |e| fill(e, "tags", json!([])));
+    each_field_def(p, |f| {
+        fill(f, "allowedRefs", json!("Any"));
+        fill(f, "allowedRefTags", json!([]));
+        fill(f, "allowOutOfLevelRef", json!(true));
+        fill(f, "autoChainRef", json!(true));
+        fill(f, "symmetricalRef", json!(false));
+        fill(f, "useForSmartColor", json!(false));
+        if !f.get("type").is_some_and(Value::is_string) {
+            let t = f.get("__type").cloned().unwrap_or(json!(""));
+            f.insert("type".into(), t);
+        }
+    });
+    each_def(p, "layers", |ld| {
+        fill(ld, "guideGridWid", json!(0));
+        fill(ld, "guideGridHei", json!(0));
+        fill(ld, "hideFieldsWhenInactive", json!(true));
+        fill(ld, "hideInList", json!(false));
+        fill(ld, "inactiveOpacity", json!(1.0));
+        fill(ld, "parallaxFactorX", json!(0.0));
+        fill(ld, "parallaxFactorY", json!(0.0));
+        fill(ld, "parallaxScaling", json!(true));
+    });
+    each_def(p, "tilesets", |t| fill(t, "tags", json!([])));
+}
+
+fn level_0_10_0_beta1(l: &mut Object, ctx: &LevelContext) {
+    let level_uid = int(l, "uid").unwrap_or(0);
+    fill(l, "iid", json!(legacy_level_iid(level_uid)));
+    fill(l, "worldDepth", json!(0));
+    if let Some(bg) = l.get("__bgColor").cloned() {
+        fill(l, "__smartColor", bg);
+    }
+    for n in objects(l, "__neighbours") {
+        if let Some(uid) = int(n, "levelUid") {
+            fill(n, "levelIid", json!(legacy_level_iid(uid)));
+        }
+    }
+    each_layer(l, |li, level_uid| {
+        let layer_def_uid = int(li, "layerDefUid").unwrap_or(0);
+        fill(li, "iid", json!(legacy_layer_iid(level_uid, layer_def_uid)));
+        for (i, e) in objects(li, "entityInstances").enumerate() {
+            fill(
+                e,
+                "iid",
+                json!(legacy_entity_iid(level_uid, layer_def_uid, i)),
+            );
+            let def = int(e, "defUid").and_then(|uid| ctx.entity_def(uid));
+            fill(e, "__tags", json!(def.map_or(&Vec::new(), |d| &d.tags)));
+
+            // {tilesetUid, srcRect: [x, y, w, h]} became a TilesetRectangle
+            if let Some(tile) = e.get("__tile").and_then(Value::as_object) {
+                let rect = tile
+                    .get("srcRect")
+                    .and_then(|src| rect_from_src(int(tile, "tilesetUid").unwrap_or(0), src));
+                if let Some(rect) = rect {
+                    e.insert("__tile".into(), rect);
+                }
+            }
+        }
+    });
+}
+
+fn project_0_10_0_beta2(p: &mut Object) {
+    each_def(p, "layers", |ld| {
+        for g in objects(ld, "autoRuleGroups") {
+            for r in objects(g, "rules") {
+                fill(r, "xOffset", json!(0));
+                fill(r, "yOffset", json!(0));
+            }
+        }
+    });
+}
+
+fn level_0_10_0_beta2(l: &mut Object, ctx: &LevelContext) {
+    each_layer(l, |li, _| {
+        for e in objects(li, "entityInstances") {
+            let def = int(e, "defUid").and_then(|uid| ctx.entity_def(uid));
+            let color = def.map_or("#FFFFFF", |d| d.color.as_str());
+            fill(e, "__smartColor", json!(color));
+        }
+    });
+}
+
+fn project_1_0_0(p: &mut Object) {
+    each_def(p, "entities", |e| fill(e, "nineSliceBorders", json!([])));
+}
+
+fn project_1_1_0(p: &mut Object) {
+    fill(p, "simplifiedExport", json!(false));
+}
+
+fn project_1_5_3(p: &mut Object) {
+    fill(p, "iid", json!(LEGACY_PROJECT_IID));
+    fill(p, "dummyWorldIid", json!(LEGACY_WORLD_IID));
+    fill(p, "customCommands", json!([]));
+    fill(p, "defaultEntityWidth", json!(16));
+    fill(p, "defaultEntityHeight", json!(16));
+    fill(p, "exportLevelBg", json!(true));
+    fill(p, "toc", json!([]));
+
+    each_def(p, "entities", |e| {
+        fill(e, "allowOutOfBounds", json!(false));
+        fill(e, "exportToToc", json!(false));
+    });
+    each_field_def(p, |f| {
+        fill(f, "editorDisplayScale", json!(1.0));
+        fill(f, "editorLinkStyle", json!("StraightArrow"));
+        fill(f, "editorShowInWorld", json!(true));
+        fill(f, "exportToToc", json!(false));
+        fill(f, "searchable", json!(false));
+    });
+    // enum value tiles went from [x, y, w, h] to a TilesetRectangle
+    each_enum_def(p, |e| {
+        let tileset_uid = int(e, "iconTilesetUid");
+        for v in objects(e, "values") {
+            let rect = tileset_uid
+                .zip(v.get("__tileSrcRect"))
+                .and_then(|(uid, src)| rect_from_src(uid, src));
+            if let Some(rect) = rect {
+                fill(v, "tileRect", rect);
+            }
+        }
+    });
+    each_def(p, "layers", |ld| {
+        fill(ld, "canSelectWhenInactive", json!(true));
+        fill(ld, "intGridValuesGroups", json!([]));
+        fill(ld, "renderInWorldView", json!(true));
+        fill(ld, "uiFilterTags", json!([]));
+        fill(ld, "useAsyncRender", json!(false));
+        for v in objects(ld, "intGridValues") {
+            fill(v, "groupUid", json!(0));
+        }
+        for g in objects(ld, "autoRuleGroups") {
+            fill(g, "biomeRequirementMode", json!(0));
+            fill(g, "requiredBiomeValues", json!([]));
+            fill(g, "usesWizard", json!(false));
+            for r in objects(g, "rules") {
+                fill(r, "alpha", json!(1.0));
+                fill(r, "invalidated", json!(false));
+                fill(r, "tileRandomXMin", json!(0));
+                fill(r, "tileRandomXMax", json!(0));
+                fill(r, "tileRandomYMin", json!(0));
+                fill(r, "tileRandomYMax", json!(0));
+                fill(r, "tileXOffset", json!(0));
+                fill(r, "tileYOffset", json!(0));
+                // single tile ids became rectangles of tile ids
+                let rects: Vec<Value> = r
+                    .get("tileIds")
+                    .and_then(Value::as_array)
+                    .map(|ids| ids.iter().map(|id| json!([id])).collect())
+                    .unwrap_or_default();
+                fill(r, "tileRectsIds", json!(rects));
+            }
+        }
+    });
+}
+
+fn level_1_5_3(l: &mut Object, ctx: &LevelContext) {
+    let world_x = int(l, "worldX").unwrap_or(0);
+    let world_y = int(l, "worldY").unwrap_or(0);
+    let linear = is_linear(ctx.world_layout);
+    each_layer(l, |li, _| {
+        for key in ["gridTiles", "autoLayerTiles"] {
+            objects(li, key).for_each(|t| fill(t, "a", json!(1.0)));
+        }
+        if linear {
+            return;
+        }
+        for e in objects(li, "entityInstances") {
+            let px = e.get("px").and_then(Value::as_array);
+            let coord = |i: usize| px.and_then(|px| px.get(i)).and_then(Value::as_i64);
+            if let (Some(x), Some(y)) = (coord(0), coord(1)) {
+                fill(e, "__worldX", json!(world_x + x));
+                fill(e, "__worldY", json!(world_y + y));
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LayerInstance, LdtkError};
+
+    const GAMES: [(&str, &[u8], SchemaVersion); 7] = [
+        ("0.7.2", include_bytes!("../assets/game_0-7-2.ldtk"), V0_7_0),
+        ("0.8.1", include_bytes!("../assets/game_0-8-1.ldtk"), V0_8_1),
+        ("0.9.2", include_bytes!("../assets/game_0-9-2.ldtk"), V0_9_2),
+        ("0.9.3", include_bytes!("../assets/game_0-9-3.ldtk"), V0_9_3),
+        (
+            "0.10.0-beta1",
+            include_bytes!("../assets/game_0-10-beta1.ldtk"),
+            V0_10_0Beta1,
+        ),
+        ("1.1.0", include_bytes!("../assets/game_1-1-0.ldtk"), V1_1_0),
+        ("1.1.3", include_bytes!("../assets/game_1-1-3.ldtk"), V1_1_3),
+    ];
+
+    fn layer<'a>(project: &'a Project, identifier: &str) -> &'a LayerInstance {
+        project.levels[0]
+            .layer_instances
+            .iter()
+            .flatten()
+            .find(|l| l.identifier == identifier)
+            .unwrap()
+    }
+
+    #[test]
+    fn every_version_upgrades() {
+        for (version, b, schema) in GAMES {
+            assert_eq!(SchemaVersion::detect(b), Some(schema), "{version}");
+            let project = Project::try_from_slice(b).unwrap_or_else(|e| panic!("{version}: {e}"));
+            assert_eq!(project.levels.len(), 1, "{version}");
+            let layers = project.levels[0].layer_instances.as_ref().unwrap();
+            assert!(!layers.is_empty(), "{version}");
+        }
+    }
+
+    #[test]
+    fn external_levels_upgrade() {
+        let project = Project::try_new("assets/SeparateLevelFiles.ldtk").unwrap();
+        assert_eq!(project.levels.len(), 3);
+        assert!(project.levels.iter().all(|l| l.layer_instances.is_some()));
+    }
+
+    #[test]
+    fn int_grid_csv_from_sparse_int_grid() {
+        let current = Project::try_from_slice(GAMES[6].1).unwrap();
+        let expected = &layer(&current, "IntGrid").int_grid_csv;
+        assert!(expected.iter().any(|&v| v != 0));
+        for (version, b, _) in &GAMES[..4] {
+            let project = Project::try_from_slice(b).unwrap();
+            assert_eq!(
+                &layer(&project, "IntGrid").int_grid_csv,
+                expected,
+                "{version}"
+            );
+        }
+    }
+
+    #[test]
+    fn tile_rect_from_tile_id() {
+        for (version, b, _) in GAMES {
+            let project = Project::try_from_slice(b).unwrap();
+            let bird = project
+                .defs
+                .entities
+                .iter()
+                .find(|e| e.identifier == "Bird");
+            let rect = bird.and_then(|e| e.tile_rect.as_ref()).unwrap();
+            let found = (rect.tileset_uid, rect.x, rect.y, rect.w, rect.h);
+            assert_eq!(found, (18, 56, 32, 8, 8), "{version}");
+        }
+    }
+
+    #[test]
+    fn tile_rect_from_tile_src_rect() {
+        let mut v: Value = serde_json::from_slice(GAMES[6].1).unwrap();
+        v["defs"]["enums"] = json!([{
+            "identifier": "Item",
+            "uid": 500,
+            "iconTilesetUid": 18,
+            "tags": [],
+            "externalRelPath": null,
+            "externalFileChecksum": null,
+            "values": [{ "id": "Key", "tileId": 3, "__tileSrcRect": [24, 0, 8, 8], "color": 0 }]
+        }]);
+        let project = Project::try_from_slice(&serde_json::to_vec(&v).unwrap()).unwrap();
+        let rect = project.defs.enums[0].values[0].tile_rect.as_ref().unwrap();
+        assert_eq!(
+            (rect.tileset_uid, rect.x, rect.y, rect.w, rect.h),
+            (18, 24, 0, 8, 8)
+        );
+    }
+
+    #[test]
+    fn legacy_iids() {
+        for (version, b, _) in &GAMES[..4] {
+            let project = Project::try_from_slice(b).unwrap();
+            assert_eq!(project.iid, LEGACY_PROJECT_IID, "{version}");
+            for level in &project.levels {
+                assert_eq!(
+                    level.iid,
+                    format!("legacy-level-{}", level.uid),
+                    "{version}"
+                );
+                for layer in level.layer_instances.iter().flatten() {
+                    let iid = legacy_layer_iid(level.uid, layer.layer_def_uid);
+                    assert_eq!(layer.iid, iid, "{version}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn malformed_old_files_are_errors() {
+        let (_, b, _) = GAMES[3];
+        let truncated = Project::try_from_slice(&b[..b.len() / 2]);
+        assert!(matches!(truncated, Err(LdtkError::Syntax { .. })));
+
+        let mut v: Value = serde_json::from_slice(b).unwrap();
+        v["levels"] = json!("not a list");
+        let wrong = Project::try_from_slice(&serde_json::to_vec(&v).unwrap());
+        assert!(matches!(wrong, Err(LdtkError::Schema { .. })));
+    }
+
+    #[test]
+    fn version_key_as_a_value() {
+        let b = br#"{ "identifier": "jsonVersion", "jsonVersion": "0.9.3" }"#;
+        assert_eq!(peek_string(b, "jsonVersion").as_deref(), Some("0.9.3"));
+        assert_eq!(
+            peek_string(br#"{ "a": "jsonVersion" }"#, "jsonVersion"),
+            None
+        );
+    }
+}
@@ -0,0 +1,554 @@
+// Checks custom field values against the constraints of their
+// definitions. The editor enforces these as values are entered, but
+// nothing does when a file is edited by hand or written by another tool.
+
+use crate::{
+    AllowedRefs, EntityInstance, EntityRef, FieldDefinition, FieldError, FieldInstance, FieldValue,
+    Level, Project, ProjectIndex,
+};
+use std::fmt;
+
+#[cfg(feature = "regex")]
+use std::collections::HashMap;
+
+/// Where a field value is: its level and, for entity fields, its layer
+/// and entity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldLocation {
+    pub world_iid: String,
+    pub level_iid: String,
+    pub level_identifier: String,
+    /// None for level fields.
+    pub layer_iid: Option<String>,
+    pub entity_iid: Option<String>,
+    pub entity_identifier: Option<String>,
+    pub field: String,
+    /// The element at fault, for array fields.
+    pub index: Option<usize>,
+}
+
+impl fmt::Display for FieldLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "level {}", self.level_identifier)?;
+        if let (Some(identifier), Some(iid)) = (&self.entity_identifier, &self.entity_iid) {
+            write!(f, ", entity {} ({})", identifier, iid)?;
+        }
+        write!(f, ", field {}", self.field)?;
+        if let Some(i) = self.index {
+            write!(f, "[{}]", i)?;
+        }
+        Ok(())
+    }
+}
+
+/// A field value that breaks a constraint of its definition.
+#[derive(Debug)]
+pub struct FieldViolation {
+    pub location: FieldLocation,
+    pub kind: ViolationKind,
+}
+
+impl fmt::Display for FieldViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.kind)
+    }
+}
+
+/// The constraint a [`FieldViolation`] breaks.
+#[derive(Debug)]
+pub enum ViolationKind {
+    /// No field definition has the instance's `def_uid`.
+    UnknownDefinition {
+        def_uid: i64,
+    },
+
+    /// The value can't be read as the field's type.
+    Invalid(FieldError),
+
+    /// The value is null but the definition doesn't allow it.
+    Null,
+
+    BelowMin {
+        value: f64,
+        min: f64,
+    },
+
+    AboveMax {
+        value: f64,
+        max: f64,
+    },
+
+    /// The string doesn't match the definition's regex.
+    NoMatch {
+        value: String,
+        regex: String,
+    },
+
+    /// The definition's regex isn't one we can compile.
+    BadRegex {
+        regex: String,
+        message: String,
+    },
+
+    TooShort {
+        len: usize,
+        min: usize,
+    },
+
+    TooLong {
+        len: usize,
+        max: usize,
+    },
+
+    /// The file's extension isn't one of `accept_file_types`.
+    FileType {
+        path: String,
+        accepted: Vec<String>,
+    },
+
+    /// The entity isn't of the type the definition allows (`OnlySame` or
+    /// `OnlySpecificEntity`).
+    RefWrongEntity {
+        entity_iid: String,
+        identifier: String,
+        expected: String,
+    },
+
+    /// The entity has none of the `allowed_ref_tags` (`OnlyTags`).
+    RefWrongTags {
+        entity_iid: String,
+        tags: Vec<String>,
+        allowed: Vec<String>,
+    },
+
+    /// The entity is in another level and `allow_out_of_level_ref` is off.
+    RefOutOfLevel {
+        entity_iid: String,
+        level_iid: String,
+    },
+
+    /// The entity's level is loaded but the entity isn't in it.
+    RefMissing {
+        entity_iid: String,
+    },
+}
+
+impl fmt::Display for ViolationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ViolationKind::UnknownDefinition { def_uid } => {
+                write!(f, "no field definition with uid {}", def_uid)
+            }
+            ViolationKind::Invalid(e) => write!(f, "{}", e),
+            ViolationKind::Null => write!(f, "value is null"),
+            ViolationKind::BelowMin { value, min } => {
+                write!(f, "{} is less than the minimum {}", value, min)
+            }
+            ViolationKind::AboveMax { value, max } => {
+                write!(f, "{} is more than the maximum {}", value, max)
+            }
+            ViolationKind::NoMatch { value, regex } => {
+                write!(f, "{:?} doesn't match {}", value, regex)
+            }
+            ViolationKind::BadRegex { regex, message } => {
+                write!(f, "can't use regex {}: {}", regex, message)
+            }
+            ViolationKind::TooShort { len, min } => {
+                write!(f, "{} elements, at least {} needed", len, min)
+            }
+            ViolationKind::TooLong { len, max } => {
+                write!(f, "{} elements, at most {} allowed", len, max)
+            }
+            ViolationKind::FileType { path, accepted } => {
+                write!(f, "{} is not one of {}", path, accepted.join(", "))
+            }
+            ViolationKind::RefWrongEntity {
+                entity_iid,
+                identifier,
+                expected,
+            } => write!(
+                f,
+                "refers to {} ({}), expected {}",
+                identifier, entity_iid, expected
+            ),
+            ViolationKind::RefWrongTags {
+                entity_iid,
+                tags,
+                allowed,
+            } => write!(
+                f,
+                "refers to {} tagged [{}], expected one of [{}]",
+                entity_iid,
+                tags.join(", "),
+                allowed.join(", ")
+            ),
+            ViolationKind::RefOutOfLevel {
+                entity_iid,
+                level_iid,
+            } => write!(
+                f,
+                "refers to {} in another level ({})",
+                entity_iid, level_iid
+            ),
+            ViolationKind::RefMissing { entity_iid } => {
+                write!(f, "refers to {} which doesn't exist", entity_iid)
+            }
+        }
+    }
+}
+
+impl Project {
+    /// Check the custom fields of every loaded level and entity against
+    /// their definitions: nulls, `min`/`max`, `regex`, array lengths,
+    /// `accept_file_types` and the entity reference rules. Returns every
+    /// violation found, in level order.
+    ///
+    /// Regexes are only checked with the `regex` feature.
+    pub fn validate_fields(&self) -> Vec<FieldViolation> {
+        let mut v = Validator {
+            index: self.index(),
+            #[cfg(feature = "regex")]
+            regexes: HashMap::new(),
+            violations: Vec::new(),
+        };
+
+        for world in self.iter_worlds() {
+            for level in world.levels {
+                let location = FieldLocation {
+                    world_iid: world.iid.to_string(),
+                    level_iid: level.iid.clone(),
+                    level_identifier: level.identifier.clone(),
+                    layer_iid: None,
+                    entity_iid: None,
+                    entity_identifier: None,
+                    field: String::new(),
+                    index: None,
+                };
+                for field in &level.field_instances {
+                    v.check(&location, level, None, field);
+                }
+                for layer in level.layer_instances.iter().flatten() {
+                    for entity in &layer.entity_instances {
+                        let location = FieldLocation {
+                            layer_iid: Some(layer.iid.clone()),
+                            entity_iid: Some(entity.iid.clone()),
+                            entity_identifier: Some(entity.identifier.clone()),
+                            ..location.clone()
+                        };
+                        for field in &entity.field_instances {
+                            v.check(&location, level, Some(entity), field);
+                        }
+                    }
+                }
+            }
+        }
+        v.violations
+    }
+}
+
+struct Validator<'a> {
+    index: ProjectIndex<'a>,
+    // compiled regexes by field definition uid
+    #[cfg(feature = "regex")]
+    regexes: HashMap<i64, Result<regex::Regex, String>>,
+    violations: Vec<FieldViolation>,
+}
+
+impl<'a> Validator<'a> {
+    fn check(
+        &mut self,
+        location: &FieldLocation,
+        level: &Level,
+        entity: Option<&EntityInstance>,
+        field: &FieldInstance,
+    ) {
+        let location = FieldLocation {
+            field: field.identifier.clone(),
+            ..location.clone()
+        };
+        let Some(def) = self.index.get_field_def(field.def_uid) else {
+            self.report(
+                &location,
+                ViolationKind::UnknownDefinition {
+                    def_uid: field.def_uid,
+                },
+            );
+            return;
+        };
+        let value = match field.typed_value() {
+            Ok(value) => value,
+            Err(e) => {
+                self.report(&location, ViolationKind::Invalid(e));
+                return;
+            }
+        };
+
+        if !def.is_array {
+            self.check_value(&location, level, entity, def, &value);
+            return;
+        }
+        let items = match &value {
+            FieldValue::Array(items) => items.as_slice(),
+            _ => &[],
+        };
+        let len = items.len();
+        // negative bounds don't mean anything, they are ignored
+        let bound = |b: Option<i64>| b.and_then(|b| usize::try_from(b).ok());
+        if let Some(min) = bound(def.array_min_length)
+            && len < min
+        {
+            self.report(&location, ViolationKind::TooShort { len, min });
+        }
+        if let Some(max) = bound(def.array_max_length)
+            && len > max
+        {
+            self.report(&location, ViolationKind::TooLong { len, max });
+        }
+        for (i, item) in items.iter().enumerate() {
+            let location = FieldLocation {
+                index: Some(i),
+                ..location.clone()
+            };
+            self.check_value(&location, level, entity, def, item);
+        }
+    }
+
+    // Check a single value, or an element of an array field.
+    fn check_value(
+        &mut self,
+        location: &FieldLocation,
+        level: &Level,
+        entity: Option<&EntityInstance>,
+        def: &FieldDefinition,
+        value: &FieldValue,
+    ) {
+        match value {
+            FieldValue::Null if !def.can_be_null => self.report(location, ViolationKind::Null),
+            FieldValue::Int(i) => self.check_range(location, def, *i as f64),
+            FieldValue::Float(f) => self.check_range(location, def, *f),
+            FieldValue::String(s) | FieldValue::Multilines(s) => self.check_regex(location, def, s),
+            FieldValue::FilePath(path) => self.check_file_type(location, def, path),
+            FieldValue::EntityRef(r) => self.check_ref(location, level, entity, def, r),
+            _ => {}
+        }
+    }
+
+    fn check_range(&mut self, location: &FieldLocation, def: &FieldDefinition, value: f64) {
+        if let Some(min) = def.min
+            && value < min
+        {
+            self.report(location, ViolationKind::BelowMin { value, min });
+        }
+        if let Some(max) = def.max
+            && value > max
+        {
+            self.report(location, ViolationKind::AboveMax { value, max });
+        }
+    }
+
+    #[cfg(feature = "regex")]
+    fn check_regex(&mut self, location: &FieldLocation, def: &FieldDefinition, value: &str) {
+        let Some(source) = &def.regex else {
+            return;
+        };
+        let regex = self
+            .regexes
+            .entry(def.uid)
+            .or_insert_with(|| compile_regex(source));
+        let kind = match regex {
+            Ok(regex) if regex.is_match(value) => return,
+            Ok(_) => ViolationKind::NoMatch {
+                value: value.to_string(),
+                regex: source.clone(),
+            },
+            Err(message) => ViolationKind::BadRegex {
+                regex: source.clone(),
+                message: message.clone(),
+            },
+        };
+        self.report(location, kind);
+    }
+
+    #[cfg(not(feature = "regex"))]
+    fn check_regex(&mut self, _location: &FieldLocation, _def: &FieldDefinition, _value: &str) {}
+
+    fn check_file_type(&mut self, location: &FieldLocation, def: &FieldDefinition, path: &str) {
+        let Some(accepted) = def.accept_file_types.as_ref().filter(|a| !a.is_empty()) else {
+            return;
+        };
+        let path_lower = path.to_lowercase();
+        if !accepted
+            .iter()
+            .any(|ext| path_lower.ends_with(&ext.to_lowercase()))
+        {
+            self.report(
+                location,
+                ViolationKind::FileType {
+                    path: path.to_string(),
+                    accepted: accepted.clone(),
+                },
+            );
+        }
+    }
+
+    fn check_ref(
+        &mut self,
+        location: &FieldLocation,
+        level: &Level,
+        entity: Option<&EntityInstance>,
+        def: &FieldDefinition,
+        r: &EntityRef,
+    ) {
+        if !def.allow_out_of_level_ref && r.level_iid != level.iid {
+            self.report(
+                location,
+                ViolationKind::RefOutOfLevel {
+                    entity_iid: r.entity_iid.clone(),
+                    level_iid: r.level_iid.clone(),
+                },
+            );
+        }
+
+        let Some(target) = self.index.get_entity_by_iid(&r.entity_iid) else {
+            // only an error if we could have found it
+            let loaded = self
+                .index
+                .get_level_by_iid(&r.level_iid)
+                .is_some_and(|l| l.layer_instances.is_some());
+            if loaded {
+                self.report(
+                    location,
+                    ViolationKind::RefMissing {
+                        entity_iid: r.entity_iid.clone(),
+                    },
+                );
+            }
+            return;
+        };
+        let expected_uid = match def.allowed_refs {
+            AllowedRefs::Any => None,
+            AllowedRefs::OnlySame => entity.map(|e| e.def_uid),
+            AllowedRefs::OnlySpecificEntity => def.allowed_refs_entity_uid,
+            AllowedRefs::OnlyTags => {
+                if !target.tags.iter().any(|t| def.allowed_ref_tags.contains(t)) {
+                    self.report(
+                        location,
+                        ViolationKind::RefWrongTags {
+                            entity_iid: target.iid.clone(),
+                            tags: target.tags.clone(),
+                            allowed: def.allowed_ref_tags.clone(),
+                        },
+                    );
+                }
+                None
+            }
+        };
+        if let Some(uid) = expected_uid
+            && target.def_uid != uid
+        {
+            let expected = self.index.get_entity_def(uid).map_or_else(
+                || format!("entity definition {uid}"),
+                |e| e.identifier.clone(),
+            );
+            self.report(
+                location,
+                ViolationKind::RefWrongEntity {
+                    entity_iid: target.iid.clone(),
+                    identifier: target.identifier.clone(),
+                    expected,
+                },
+            );
+        }
+    }
+
+    fn report(&mut self, location: &FieldLocation, kind: ViolationKind) {
+        self.violations.push(FieldViolation {
+            location: location.clone(),
+            kind,
+        });
+    }
+}
+
+// LDtk stores regexes the JavaScript way, as `/pattern/flags`. Only the
+// `i` flag changes what matches.
+#[cfg(feature = "regex")]
+fn compile_regex(source: &str) -> Result<regex::Regex, String> {
+    let (pattern, flags) = match source.strip_prefix('/').and_then(|s| s.rsplit_once('/')) {
+        Some((pattern, flags)) => (pattern, flags),
+        None => (source, ""),
+    };
+    regex::RegexBuilder::new(pattern)
+        .case_insensitive(flags.contains('i'))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // A project whose first level has an Array<Int> field of three values,
+    // with the given array bounds.
+    fn project(min: Option<i64>, max: Option<i64>) -> Project {
+        let mut project = Project::from_slice(include_bytes!("../assets/game_1-1-3.ldtk"));
+        let mut def = project.defs.entities[0].field_defs[0].clone();
+        def.uid = 9000;
+        def.identifier = "counts".to_string();
+        def.field_definition_type = "Array<Int>".to_string();
+        def.is_array = true;
+        def.array_min_length = min;
+        def.array_max_length = max;
+        project.defs.level_fields.push(def);
+        project.levels[0].field_instances.push(FieldInstance {
+            identifier: "counts".to_string(),
+            tile: None,
+            field_instance_type: "Array<Int>".to_string(),
+            value: Some(json!([1, 2, 3])),
+            def_uid: 9000,
+            real_editor_values: Vec::new(),
+        });
+        project
+    }
+
+    fn lengths(min: Option<i64>, max: Option<i64>) -> Vec<String> {
+        project(min, max)
+            .validate_fields()
+            .into_iter()
+            .map(|v| match v.kind {
+                ViolationKind::TooShort { len, min } => format!("{len} < {min}"),
+                ViolationKind::TooLong { len, max } => format!("{len} > {max}"),
+                kind => panic!("unexpected {kind}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn array_lengths() {
+        assert!(lengths(Some(2), Some(4)).is_empty());
+        assert!(lengths(Some(3), Some(3)).is_empty());
+        assert_eq!(lengths(Some(5), None), ["3 < 5"]);
+        assert_eq!(lengths(None, Some(1)), ["3 > 1"]);
+    }
+
+    #[test]
+    fn negative_array_bounds_are_ignored() {
+        assert!(lengths(Some(-1), None).is_empty());
+        assert!(lengths(None, Some(-1)).is_empty());
+        assert_eq!(lengths(Some(-1), Some(2)), ["3 > 2"]);
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex_flags() {
+        let re = compile_regex("/^a+$/i").unwrap();
+        assert!(re.is_match("aAa"));
+        let re = compile_regex("/^a+$/").unwrap();
+        assert!(re.is_match("aaa") && !re.is_match("AAA"));
+        let re = compile_regex("/^a/b$/g").unwrap();
+        assert!(re.is_match("a/b"));
+        let re = compile_regex("^b+$").unwrap();
+        assert!(re.is_match("bb"));
+        assert!(compile_regex("/(/").is_err());
+    }
+}
@@ -0,0 +1,107 @@
+// Uniform access to the worlds of a project. Projects with the
+// MultiWorlds flag keep their levels in `worlds`, the others keep them
+// (along with the layout and grid size) on the project itself, in what
+// LDtk calls the dummy world.
+
+use crate::{Level, Project, World, WorldLayout};
+
+// identifier LDtk gives the dummy world
+const DUMMY_WORLD_IDENTIFIER: &str = "World";
+
+// LDtk's defaults for values the project may leave out
+const DEFAULT_LEVEL_SIZE: i64 = 256;
+const DEFAULT_WORLD_GRID_SIZE: i64 = 256;
+
+/// A world of a project, borrowed either from one of the project's
+/// `worlds` or, in single world projects, from the project itself.
+#[derive(Debug, Clone, Copy)]
+pub struct WorldRef<'a> {
+    /// The world's iid (the project's `dummy_world_iid` for single world
+    /// projects).
+    pub iid: &'a str,
+    pub identifier: &'a str,
+    pub levels: &'a [Level],
+    pub world_layout: Option<&'a WorldLayout>,
+    pub world_grid_width: i64,
+    pub world_grid_height: i64,
+    pub default_level_width: i64,
+    pub default_level_height: i64,
+}
+
+impl<'a> From<&'a World> for WorldRef<'a> {
+    fn from(w: &'a World) -> Self {
+        WorldRef {
+            iid: &w.iid,
+            identifier: &w.identifier,
+            levels: &w.levels,
+            world_layout: w.world_layout.as_ref(),
+            world_grid_width: w.world_grid_width,
+            world_grid_height: w.world_grid_height,
+            default_level_width: w.default_level_width,
+            default_level_height: w.default_level_height,
+        }
+    }
+}
+
+impl<'a> WorldRef<'a> {
+    fn dummy(p: &'a Project) -> Self {
+        WorldRef {
+            iid: &p.dummy_world_iid,
+            identifier: DUMMY_WORLD_IDENTIFIER,
+            levels: &p.levels,
+            world_layout: p.world_layout.as_ref(),
+            world_grid_width: p.world_grid_width.unwrap_or(DEFAULT_WORLD_GRID_SIZE),
+            world_grid_height: p.world_grid_height.unwrap_or(DEFAULT_WORLD_GRID_SIZE),
+            default_level_width: p.default_level_width.unwrap_or(DEFAULT_LEVEL_SIZE),
+            default_level_height: p.default_level_height.unwrap_or(DEFAULT_LEVEL_SIZE),
+        }
+    }
+}
+
+impl Project {
+    /// Every world of the project. Projects that don't use multiple worlds
+    /// have exactly one.
+    pub fn iter_worlds(&self) -> impl Iterator<Item = WorldRef<'_>> {
+        let dummy = self.worlds.is_empty().then(|| WorldRef::dummy(self));
+        dummy
+            .into_iter()
+            .chain(self.worlds.iter().map(WorldRef::from))
+    }
+
+    pub fn get_world_by_iid(&self, iid: &str) -> Option<WorldRef<'_>> {
+        self.iter_worlds().find(|w| w.iid == iid)
+    }
+
+    pub fn get_world_by_identifier(&self, identifier: &str) -> Option<WorldRef<'_>> {
+        self.iter_worlds().find(|w| w.identifier == identifier)
+    }
+
+    /// The world a level (found by iid) is in.
+    pub fn world_of_level(&self, level_iid: &str) -> Option<WorldRef<'_>> {
+        self.iter_worlds()
+            .find(|w| w.levels.iter().any(|l| l.iid == level_iid))
+    }
+
+    /// The levels of every world, in order.
+    pub fn iter_levels(&self) -> impl Iterator<Item = &Level> {
+        self.levels
+            .iter()
+            .chain(self.worlds.iter().flat_map(|w| w.levels.iter()))
+    }
+
+    // The levels with the world they are in, in iter_levels() order.
+    pub(crate) fn iter_levels_with_world(&self) -> impl Iterator<Item = (WorldRef<'_>, &Level)> {
+        let dummy = WorldRef::dummy(self);
+        let worlds = self.worlds.iter().map(WorldRef::from);
+        self.levels
+            .iter()
+            .map(move |level| (dummy, level))
+            .chain(worlds.flat_map(|world| world.levels.iter().map(move |level| (world, level))))
+    }
+
+    pub fn iter_levels_mut(&mut self) -> impl Iterator<Item = &mut Level> {
+        self.levels
+            .iter_mut()
+            .chain(self.worlds.iter_mut().flat_map(|w| w.levels.iter_mut()))
+    }
+}